@@ -35,8 +35,9 @@ pub fn calculate_assets_from_shares(
 }
 
 pub fn validate_allocation_weights(weights: &[u16]) -> bool {
-    let total: u16 = weights.iter().sum();
-    total == BASIS_POINTS
+    // Sum in u32 so large weights cannot overflow back to 10_000
+    let total: u32 = weights.iter().map(|w| *w as u32).sum();
+    total == BASIS_POINTS as u32
 }
 
 #[cfg(test)]
@@ -64,5 +65,6 @@ mod tests {
     fn test_validate_allocation_weights() {
         assert!(validate_allocation_weights(&[5000, 3000, 2000]));
         assert!(!validate_allocation_weights(&[5000, 3000, 1000]));
+        assert!(!validate_allocation_weights(&[60000, 15536]));
     }
 }
\ No newline at end of file
@@ -1,12 +1,17 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
+use std::cmp::Reverse;
+
 use cosmwasm_std::{
-    ensure, to_json_binary, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Order,
-    Response, StdResult, Timestamp, Uint128,
+    ensure, to_json_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Empty, Env, Int128,
+    MessageInfo, Order, QueryRequest, Response, StdError, StdResult, Storage, Timestamp, Uint128,
+    WasmQuery,
 };
 use cw2::set_contract_version;
 use cw_storage_plus::Bound;
+use seimoney_common::math::{calculate_fee, validate_allocation_weights, BASIS_POINTS};
+use seimoney_common::validation::validate_fee_bps;
 
 use crate::error::ContractError;
 use crate::error::ContractError::*;
@@ -17,7 +22,7 @@ use crate::state::*;
 const CONTRACT_NAME: &str = "crates.io:seimoney-groups";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-#[entry_point]
+#[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
@@ -28,18 +33,24 @@ pub fn instantiate(
         Some(a) => deps.api.addr_validate(&a)?,
         None => info.sender.clone(),
     };
+    let alias_contract = msg
+        .alias_contract
+        .map(|a| deps.api.addr_validate(&a))
+        .transpose()?;
     let cfg = Config {
         admin,
         default_denom: msg.default_denom,
         max_participants: msg.max_participants.unwrap_or(100),
+        alias_contract,
     };
     CONFIG.save(deps.storage, &cfg)?;
     NEXT_POOL_ID.save(deps.storage, &1u64)?;
+    NEXT_EXPENSE_GROUP_ID.save(deps.storage, &1u64)?;
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     Ok(Response::new().add_attribute("action", "instantiate"))
 }
 
-#[entry_point]
+#[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
     env: Env,
@@ -52,7 +63,24 @@ pub fn execute(
             max_participants,
             memo,
             expiry_ts,
-        } => exec_create_pool(deps, env, info, target, max_participants, memo, expiry_ts),
+            allowlist,
+            beneficiaries,
+            min_contribution,
+            max_contribution_per_address,
+            hard_cap,
+        } => {
+            let terms = PoolTerms {
+                max_participants,
+                memo,
+                expiry_ts,
+                allowlist,
+                beneficiaries,
+                min_contribution,
+                max_contribution_per_address,
+                hard_cap,
+            };
+            exec_create_pool(deps, env, info, target, terms)
+        }
         ExecuteMsg::Contribute { pool_id, amount } => {
             exec_contribute(deps, env, info, pool_id, amount)
         }
@@ -61,18 +89,84 @@ pub fn execute(
         ExecuteMsg::RefundContribution { pool_id } => {
             exec_refund_contribution(deps, env, info, pool_id)
         }
+        ExecuteMsg::AddRewardTier {
+            pool_id,
+            min_amount,
+            description,
+            limit,
+        } => exec_add_reward_tier(deps, info, pool_id, min_amount, description, limit),
+        ExecuteMsg::ProcessExpired { limit } => exec_process_expired(deps, env, limit),
+        ExecuteMsg::AddParticipants {
+            pool_id,
+            participants,
+        } => exec_add_participants(deps, info, pool_id, participants),
+        ExecuteMsg::RemoveParticipants {
+            pool_id,
+            participants,
+        } => exec_remove_participants(deps, info, pool_id, participants),
+        ExecuteMsg::CreateRosca {
+            members,
+            contribution,
+            period_secs,
+            start_ts,
+            randomize_order,
+            late_penalty_bps,
+            memo,
+        } => {
+            let terms = RoscaTerms {
+                members,
+                contribution,
+                period_secs,
+                start_ts,
+                randomize_order,
+                late_penalty_bps,
+                memo,
+            };
+            exec_create_rosca(deps, env, info, terms)
+        }
+        ExecuteMsg::CreateExpenseGroup { members } => {
+            exec_create_expense_group(deps, env, info, members)
+        }
+        ExecuteMsg::RecordExpense {
+            group_id,
+            payer,
+            amount,
+            split,
+            memo,
+        } => exec_record_expense(deps, info, group_id, payer, amount, split, memo),
+        ExecuteMsg::SettleUp { group_id } => exec_settle_up(deps, info, group_id),
     }
 }
 
+/// Optional settings of a new pool.
+struct PoolTerms {
+    max_participants: Option<u32>,
+    memo: Option<String>,
+    expiry_ts: Option<u64>,
+    allowlist: Option<Vec<Participant>>,
+    beneficiaries: Option<Vec<(String, u16)>>,
+    min_contribution: Option<Uint128>,
+    max_contribution_per_address: Option<Uint128>,
+    hard_cap: Option<Uint128>,
+}
+
 fn exec_create_pool(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     target: Coin,
-    _max_participants: Option<u32>,
-    memo: Option<String>,
-    expiry_ts: Option<u64>,
+    terms: PoolTerms,
 ) -> Result<Response, ContractError> {
+    let PoolTerms {
+        max_participants,
+        memo,
+        expiry_ts,
+        allowlist,
+        beneficiaries,
+        min_contribution,
+        max_contribution_per_address,
+        hard_cap,
+    } = terms;
     let cfg = CONFIG.load(deps.storage)?;
 
     // Validate denom
@@ -84,6 +178,33 @@ fn exec_create_pool(
         ensure!(Timestamp::from_seconds(ts) > env.block.time, PoolExpired);
     }
 
+    // Contribution limits and stretch goal
+    ensure!(
+        min_contribution.is_none_or(|min| !min.is_zero())
+            && max_contribution_per_address.is_none_or(|max| {
+                !max.is_zero() && min_contribution.is_none_or(|min| min <= max)
+            })
+            && hard_cap.is_none_or(|cap| cap > target.amount && expiry_ts.is_some()),
+        InvalidContributionLimits
+    );
+
+    // Per-pool cap can only tighten the global one
+    let max_participants = max_participants.unwrap_or(cfg.max_participants);
+    ensure!(
+        max_participants > 0 && max_participants <= cfg.max_participants,
+        InvalidMaxParticipants
+    );
+
+    let allowlist = match allowlist {
+        Some(list) => Some(resolve_participants(deps.as_ref(), list)?),
+        None => None,
+    };
+
+    let beneficiaries = match beneficiaries {
+        Some(list) => validate_beneficiaries(deps.as_ref(), list)?,
+        None => vec![(info.sender.clone(), BASIS_POINTS)],
+    };
+
     let mut id = NEXT_POOL_ID.load(deps.storage)?;
     let pool = Pool {
         id,
@@ -91,14 +212,25 @@ fn exec_create_pool(
         target: target.clone(),
         current: Uint128::zero(),
         participants: vec![],
+        max_participants,
+        allowlist,
+        beneficiaries,
+        min_contribution,
+        max_contribution_per_address,
+        hard_cap,
         memo,
         created_at: env.block.time.seconds(),
         expiry_ts,
         distributed: false,
         cancelled: false,
+        kind: PoolKind::Target,
+        tiers: vec![],
     };
 
-    POOLS.save(deps.storage, id, &pool)?;
+    pools().save(deps.storage, id, &pool)?;
+    if let Some(ts) = expiry_ts {
+        EXPIRY_QUEUE.save(deps.storage, (ts, id), &Empty {})?;
+    }
     id += 1;
     NEXT_POOL_ID.save(deps.storage, &id)?;
 
@@ -120,7 +252,7 @@ fn exec_contribute(
     amount: Coin,
 ) -> Result<Response, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
-    let mut pool = POOLS
+    let mut pool = pools()
         .load(deps.storage, pool_id)
         .map_err(|_| PoolNotFound)?;
 
@@ -128,6 +260,10 @@ fn exec_contribute(
     ensure!(!pool.distributed, PoolAlreadyDistributed);
     ensure!(!pool.cancelled, PoolAlreadyCancelled);
 
+    if matches!(pool.kind, PoolKind::Rosca(_)) {
+        return exec_contribute_rosca(deps, env, info, pool, amount);
+    }
+
     // Validate expiry
     if let Some(ts) = pool.expiry_ts {
         ensure!(Timestamp::from_seconds(ts) > env.block.time, PoolExpired);
@@ -137,99 +273,222 @@ fn exec_contribute(
     ensure!(amount.denom == cfg.default_denom, InvalidContribution);
     ensure!(amount.amount > Uint128::zero(), InvalidContribution);
 
-    // Check if target reached
-    ensure!(pool.current < pool.target.amount, TargetReached);
+    // Contributions must be backed by exactly the declared funds
+    ensure!(info.funds == [amount.clone()], FundsMismatch);
+
+    // Check if target (or the hard cap, in stretch mode) reached
+    ensure!(pool.current < pool.cap(), TargetReached);
+
+    if let Some(min) = pool.min_contribution {
+        ensure!(amount.amount >= min, BelowMinContribution);
+    }
+
+    // Only accept up to the remaining cap, the rest goes straight back
+    let remaining = pool.cap() - pool.current;
+    let accepted = amount.amount.min(remaining);
+    let excess = amount.amount - accepted;
+
+    // Check allowlist
+    if let Some(allowlist) = &pool.allowlist {
+        ensure!(allowlist.contains(&info.sender), NotAllowlisted);
+    }
 
-    // Check max participants
+    // Check max participants, returning contributors are always welcome
     ensure!(
-        pool.participants.len() < cfg.max_participants as usize,
+        pool.participants.contains(&info.sender)
+            || pool.participants.len() < pool.max_participants as usize,
         PoolFull
     );
 
-    // Add contribution
-    let contribution = Contribution {
-        contributor: info.sender.clone(),
-        amount: amount.amount,
-        contributed_at: env.block.time.seconds(),
-    };
+    // Add to any earlier contribution, keeping each deposit for audit
+    let key = (pool_id, info.sender.clone());
+    let mut contribution = CONTRIBUTIONS
+        .may_load(deps.storage, key.clone())?
+        .unwrap_or_else(|| Contribution {
+            contributor: info.sender.clone(),
+            amount: Uint128::zero(),
+            contributed_at: 0,
+            rounds_paid: 0,
+            tier: None,
+        });
+    contribution.amount += accepted;
+    if let Some(max) = pool.max_contribution_per_address {
+        ensure!(contribution.amount <= max, ContributionLimitExceeded);
+    }
+    contribution.contributed_at = env.block.time.seconds();
+    record_entry(
+        deps.storage,
+        pool_id,
+        &info.sender,
+        &ContributionEntry {
+            amount: accepted,
+            contributed_at: env.block.time.seconds(),
+            round: None,
+        },
+    )?;
 
-    CONTRIBUTIONS.save(deps.storage, (pool_id, info.sender.clone()), &contribution)?;
+    // Move the contributor up to the best tier their total now reaches
+    let mut res = Response::new();
+    let tier = qualify_tier(&pool.tiers, contribution.amount, contribution.tier);
+    if tier != contribution.tier {
+        release_tier(deps.storage, &mut pool, &info.sender, contribution.tier);
+        if let Some(t) = tier {
+            pool.tiers[t as usize].backers += 1;
+            TIER_BACKERS.save(deps.storage, (pool_id, t, info.sender.clone()), &Empty {})?;
+            res = res.add_event(evt_claim_tier(
+                pool_id,
+                t,
+                info.sender.as_str(),
+                contribution.tier,
+            ));
+        }
+        contribution.tier = tier;
+    }
+
+    CONTRIBUTIONS.save(deps.storage, key, &contribution)?;
 
     // Update pool
     if !pool.participants.contains(&info.sender) {
         pool.participants.push(info.sender.clone());
+        PARTICIPANT_POOLS.save(deps.storage, (info.sender.clone(), pool.id), &Empty {})?;
     }
-    pool.current += amount.amount;
-    POOLS.save(deps.storage, pool_id, &pool)?;
+    pool.current += accepted;
+    pools().save(deps.storage, pool_id, &pool)?;
 
-    Ok(Response::new()
+    if !excess.is_zero() {
+        res = res.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin::new(excess.u128(), amount.denom.clone())],
+        });
+    }
+
+    Ok(res
         .add_event(evt_contribute(
             pool_id,
             info.sender.as_str(),
-            &amount.amount,
+            &accepted,
             &amount.denom,
         ))
-        .add_attribute("action", "contribute"))
+        .add_attribute("action", "contribute")
+        .add_attribute("refunded", excess.to_string()))
 }
 
 fn exec_distribute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     pool_id: u64,
 ) -> Result<Response, ContractError> {
-    let mut pool = POOLS
+    let mut pool = pools()
         .load(deps.storage, pool_id)
         .map_err(|_| PoolNotFound)?;
 
-    // Only creator can distribute
-    ensure!(info.sender == pool.creator, Unauthorized);
-
     // Validate pool state
     ensure!(!pool.distributed, PoolAlreadyDistributed);
     ensure!(!pool.cancelled, PoolAlreadyCancelled);
 
-    // Check if target reached
+    if matches!(pool.kind, PoolKind::Rosca(_)) {
+        return exec_payout_rosca_round(deps, pool);
+    }
+
+    // Only creator can distribute
+    ensure!(info.sender == pool.creator, Unauthorized);
+
+    // Check if target reached. A stretch campaign keeps running until it hits
+    // the hard cap or expires having met the soft target.
     ensure!(pool.current >= pool.target.amount, InvalidPoolState);
+    if pool.hard_cap.is_some() {
+        let expired = pool
+            .expiry_ts
+            .is_some_and(|ts| Timestamp::from_seconds(ts) <= env.block.time);
+        ensure!(expired || pool.current >= pool.cap(), PoolNotExpired);
+    }
 
     // Mark as distributed
     pool.distributed = true;
-    POOLS.save(deps.storage, pool_id, &pool)?;
+    pools().save(deps.storage, pool_id, &pool)?;
 
-    // Send funds to creator
-    let bank = BankMsg::Send {
-        to_address: pool.creator.to_string(),
-        amount: vec![Coin::new(pool.current.u128(), pool.target.denom.clone())],
-    };
+    // Send funds to the beneficiaries by weight
+    let payouts = split_payouts(pool.current, &pool.beneficiaries);
+    let msgs: Vec<BankMsg> = payouts
+        .iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .map(|(addr, amount)| BankMsg::Send {
+            to_address: addr.to_string(),
+            amount: vec![Coin::new(amount.u128(), pool.target.denom.clone())],
+        })
+        .collect();
 
     Ok(Response::new()
-        .add_message(bank)
+        .add_messages(msgs)
         .add_event(evt_distribute(
             pool_id,
             pool.creator.as_str(),
             &pool.current,
             &pool.target.denom,
+            &payouts,
         ))
         .add_attribute("action", "distribute"))
 }
 
+fn validate_beneficiaries(
+    deps: Deps,
+    beneficiaries: Vec<(String, u16)>,
+) -> Result<Vec<(Addr, u16)>, ContractError> {
+    let weights: Vec<u16> = beneficiaries.iter().map(|(_, w)| *w).collect();
+    ensure!(
+        weights.iter().all(|w| *w > 0) && validate_allocation_weights(&weights),
+        InvalidBeneficiaries
+    );
+
+    let mut validated: Vec<(Addr, u16)> = Vec::with_capacity(beneficiaries.len());
+    for (addr, weight) in beneficiaries {
+        let addr = deps.api.addr_validate(&addr)?;
+        ensure!(
+            validated.iter().all(|(a, _)| *a != addr),
+            InvalidBeneficiaries
+        );
+        validated.push((addr, weight));
+    }
+    Ok(validated)
+}
+
+/// Splits `total` by weight, rounding each share down. The rounding dust goes
+/// to the first beneficiary so the whole pool is always paid out.
+fn split_payouts(total: Uint128, beneficiaries: &[(Addr, u16)]) -> Vec<(Addr, Uint128)> {
+    let mut payouts: Vec<(Addr, Uint128)> = beneficiaries
+        .iter()
+        .map(|(addr, weight)| (addr.clone(), total.multiply_ratio(*weight, BASIS_POINTS)))
+        .collect();
+    let paid: Uint128 = payouts.iter().map(|(_, amount)| *amount).sum();
+    if let Some((_, first)) = payouts.first_mut() {
+        *first += total - paid;
+    }
+    payouts
+}
+
 fn exec_cancel_pool(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     pool_id: u64,
 ) -> Result<Response, ContractError> {
-    let mut pool = POOLS
+    let mut pool = pools()
         .load(deps.storage, pool_id)
         .map_err(|_| PoolNotFound)?;
 
-    // Only creator can cancel
-    ensure!(info.sender == pool.creator, Unauthorized);
-
     // Validate pool state
     ensure!(!pool.distributed, PoolAlreadyDistributed);
     ensure!(!pool.cancelled, PoolAlreadyCancelled);
 
+    // ROSCA pots are paid out as they go, so they only wind up on a default
+    if matches!(pool.kind, PoolKind::Rosca(_)) {
+        return exec_cancel_rosca(deps, env, pool);
+    }
+
+    // Only creator can cancel
+    ensure!(info.sender == pool.creator, Unauthorized);
+
     // Check if expired
     if let Some(ts) = pool.expiry_ts {
         ensure!(
@@ -240,33 +499,94 @@ fn exec_cancel_pool(
 
     // Mark as cancelled
     pool.cancelled = true;
-    POOLS.save(deps.storage, pool_id, &pool)?;
+    pools().save(deps.storage, pool_id, &pool)?;
 
     Ok(Response::new()
         .add_event(evt_cancel_pool(pool_id, pool.creator.as_str()))
         .add_attribute("action", "cancel_pool"))
 }
 
+/// Winds up a ROSCA whose current round is still short of payments a full
+/// period after it fell due. Anyone may trigger it. Members get back what they
+/// paid into that round and any later ones; earlier rounds have already been paid out.
+fn exec_cancel_rosca(deps: DepsMut, env: Env, mut pool: Pool) -> Result<Response, ContractError> {
+    let PoolKind::Rosca(rosca) = &mut pool.kind else {
+        return Err(WrongPoolKind);
+    };
+    let members = rosca.rounds.len() as u32;
+    let round = rosca.current_round;
+    let default_ts = rosca.default_ts(round);
+    let slot = &mut rosca.rounds[round as usize];
+    ensure!(
+        slot.paid_count < members && env.block.time.seconds() >= default_ts,
+        RoundNotDefaulted { round }
+    );
+
+    // Members may already have prepaid later rounds, so refund everything not paid out
+    let mut res = Response::new();
+    let mut refunded = Uint128::zero();
+    let recipients: Vec<Addr> = rosca.rounds.iter().map(|r| r.recipient.clone()).collect();
+    for member in recipients {
+        let rounds_paid = CONTRIBUTIONS
+            .may_load(deps.storage, (pool.id, member.clone()))?
+            .map_or(0, |c| c.rounds_paid);
+        let mut amount = Uint128::zero();
+        // ROSCA rounds are paid in order, so entries are indexed by round
+        for r in round..rounds_paid {
+            let entry = CONTRIBUTION_ENTRIES.load(deps.storage, (pool.id, member.clone(), r))?;
+            rosca.rounds[r as usize].collected -= entry.amount;
+            amount += entry.amount;
+        }
+        if amount.is_zero() {
+            continue;
+        }
+        refunded += amount;
+        res = res
+            .add_message(BankMsg::Send {
+                to_address: member.to_string(),
+                amount: vec![Coin::new(amount.u128(), pool.target.denom.clone())],
+            })
+            .add_event(evt_refund_contribution(
+                pool.id,
+                member.as_str(),
+                &amount,
+                &pool.target.denom,
+            ));
+    }
+
+    pool.current -= refunded;
+    pool.cancelled = true;
+    pools().save(deps.storage, pool.id, &pool)?;
+
+    Ok(res
+        .add_event(evt_cancel_pool(pool.id, pool.creator.as_str()))
+        .add_attribute("action", "cancel_pool"))
+}
+
 fn exec_refund_contribution(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     pool_id: u64,
 ) -> Result<Response, ContractError> {
-    let pool = POOLS
+    let mut pool = pools()
         .load(deps.storage, pool_id)
         .map_err(|_| PoolNotFound)?;
 
-    // Pool must be cancelled to refund
-    ensure!(pool.cancelled, InvalidPoolState);
+    // ROSCA members are refunded when the club is cancelled
+    ensure!(matches!(pool.kind, PoolKind::Target), WrongPoolKind);
+
+    // Pool must be cancelled, or have failed to reach its target in time
+    ensure!(pool.cancelled || is_failed(&pool, &env), InvalidPoolState);
 
     // Get contribution
     let contribution = CONTRIBUTIONS
         .load(deps.storage, (pool_id, info.sender.clone()))
         .map_err(|_| InvalidContribution)?;
 
-    // Remove contribution
-    CONTRIBUTIONS.remove(deps.storage, (pool_id, info.sender.clone()));
+    // Remove contribution and give up its reward tier
+    remove_contribution(deps.storage, &mut pool, &info.sender, &contribution);
+    pools().save(deps.storage, pool_id, &pool)?;
 
     // Send refund
     let bank = BankMsg::Send {
@@ -288,7 +608,748 @@ fn exec_refund_contribution(
         .add_attribute("action", "refund_contribution"))
 }
 
-#[entry_point]
+fn exec_add_reward_tier(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_id: u64,
+    min_amount: Uint128,
+    description: String,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let mut pool = pools()
+        .load(deps.storage, pool_id)
+        .map_err(|_| PoolNotFound)?;
+
+    ensure!(info.sender == pool.creator, Unauthorized);
+    ensure!(!pool.distributed, PoolAlreadyDistributed);
+    ensure!(!pool.cancelled, PoolAlreadyCancelled);
+    ensure!(matches!(pool.kind, PoolKind::Target), WrongPoolKind);
+    ensure!(
+        !min_amount.is_zero() && limit != Some(0) && !description.is_empty(),
+        InvalidRewardTier
+    );
+
+    // Existing backers are only re-tiered on their next contribution
+    let tier = pool.tiers.len() as u32;
+    pool.tiers.push(RewardTier {
+        min_amount,
+        description,
+        limit,
+        backers: 0,
+    });
+    pools().save(deps.storage, pool_id, &pool)?;
+
+    Ok(Response::new()
+        .add_event(evt_add_reward_tier(pool_id, tier, &min_amount, limit))
+        .add_attribute("action", "add_reward_tier"))
+}
+
+/// Highest tier `total` reaches that still has room. The contributor's current
+/// tier always counts as having room for them.
+fn qualify_tier(tiers: &[RewardTier], total: Uint128, current: Option<u32>) -> Option<u32> {
+    tiers
+        .iter()
+        .enumerate()
+        .filter(|(i, t)| {
+            t.min_amount <= total
+                && (current == Some(*i as u32) || t.limit.is_none_or(|l| t.backers < l))
+        })
+        .max_by_key(|(i, t)| (t.min_amount, Reverse(*i)))
+        .map(|(i, _)| i as u32)
+}
+
+fn release_tier(storage: &mut dyn Storage, pool: &mut Pool, backer: &Addr, tier: Option<u32>) {
+    if let Some(t) = tier {
+        pool.tiers[t as usize].backers -= 1;
+        TIER_BACKERS.remove(storage, (pool.id, t, backer.clone()));
+    }
+}
+
+/// Takes a refunded contribution out of the pool's totals and participant indexes.
+fn remove_contribution(
+    storage: &mut dyn Storage,
+    pool: &mut Pool,
+    contributor: &Addr,
+    contribution: &Contribution,
+) {
+    CONTRIBUTIONS.remove(storage, (pool.id, contributor.clone()));
+    release_tier(storage, pool, contributor, contribution.tier);
+    pool.current -= contribution.amount;
+    pool.participants.retain(|p| p != contributor);
+    PARTICIPANT_POOLS.remove(storage, (contributor.clone(), pool.id));
+}
+
+/// A target pool whose expiry passed before it was funded.
+fn is_failed(pool: &Pool, env: &Env) -> bool {
+    matches!(pool.kind, PoolKind::Target)
+        && !pool.distributed
+        && !pool.cancelled
+        && pool.current < pool.target.amount
+        && pool
+            .expiry_ts
+            .is_some_and(|ts| Timestamp::from_seconds(ts) <= env.block.time)
+}
+
+fn exec_process_expired(
+    deps: DepsMut,
+    env: Env,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let mut budget = limit.unwrap_or(10).min(30) as usize;
+    let now = env.block.time.seconds();
+
+    // Everything due up to and including `now`
+    let due: Vec<(u64, u64)> = EXPIRY_QUEUE
+        .keys(
+            deps.storage,
+            None,
+            Some(Bound::exclusive((now + 1, 0))),
+            Order::Ascending,
+        )
+        .take(budget)
+        .collect::<StdResult<_>>()?;
+
+    let mut res = Response::new();
+    let mut refunded = 0u32;
+    for (ts, pool_id) in due {
+        if budget == 0 {
+            break;
+        }
+        budget -= 1;
+
+        let mut pool = pools().load(deps.storage, pool_id)?;
+        if !is_failed(&pool, &env) {
+            // Funded, distributed or cancelled in the meantime
+            EXPIRY_QUEUE.remove(deps.storage, (ts, pool_id));
+            continue;
+        }
+
+        let contributions: Vec<(Addr, Contribution)> = CONTRIBUTIONS
+            .prefix(pool_id)
+            .range(deps.storage, None, None, Order::Ascending)
+            .take(budget)
+            .collect::<StdResult<_>>()?;
+        for (addr, contribution) in contributions {
+            budget -= 1;
+            refunded += 1;
+            remove_contribution(deps.storage, &mut pool, &addr, &contribution);
+            res = res
+                .add_message(BankMsg::Send {
+                    to_address: addr.to_string(),
+                    amount: vec![Coin::new(
+                        contribution.amount.u128(),
+                        pool.target.denom.clone(),
+                    )],
+                })
+                .add_event(evt_refund_contribution(
+                    pool_id,
+                    addr.as_str(),
+                    &contribution.amount,
+                    &pool.target.denom,
+                ));
+        }
+
+        // Once nobody is left to refund the pool is closed for good
+        let remaining = CONTRIBUTIONS
+            .prefix(pool_id)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .next()
+            .is_some();
+        if !remaining {
+            pool.cancelled = true;
+            EXPIRY_QUEUE.remove(deps.storage, (ts, pool_id));
+            res = res.add_event(evt_pool_failed(pool_id, pool.creator.as_str()));
+        }
+        pools().save(deps.storage, pool_id, &pool)?;
+    }
+
+    Ok(res
+        .add_attribute("action", "process_expired")
+        .add_attribute("refunded", refunded.to_string()))
+}
+
+fn exec_add_participants(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_id: u64,
+    participants: Vec<Participant>,
+) -> Result<Response, ContractError> {
+    let mut pool = pools()
+        .load(deps.storage, pool_id)
+        .map_err(|_| PoolNotFound)?;
+
+    ensure!(info.sender == pool.creator, Unauthorized);
+    ensure!(!pool.distributed, PoolAlreadyDistributed);
+    ensure!(!pool.cancelled, PoolAlreadyCancelled);
+    ensure!(matches!(pool.kind, PoolKind::Target), WrongPoolKind);
+
+    let added = resolve_participants(deps.as_ref(), participants)?;
+    let allowlist = pool.allowlist.get_or_insert_with(Vec::new);
+    for addr in &added {
+        if !allowlist.contains(addr) {
+            allowlist.push(addr.clone());
+        }
+    }
+    pools().save(deps.storage, pool_id, &pool)?;
+
+    Ok(Response::new()
+        .add_event(evt_update_allowlist(pool_id, "add", &added))
+        .add_attribute("action", "add_participants"))
+}
+
+fn exec_remove_participants(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_id: u64,
+    participants: Vec<Participant>,
+) -> Result<Response, ContractError> {
+    let mut pool = pools()
+        .load(deps.storage, pool_id)
+        .map_err(|_| PoolNotFound)?;
+
+    ensure!(info.sender == pool.creator, Unauthorized);
+    ensure!(!pool.distributed, PoolAlreadyDistributed);
+    ensure!(!pool.cancelled, PoolAlreadyCancelled);
+    ensure!(matches!(pool.kind, PoolKind::Target), WrongPoolKind);
+
+    let removed = resolve_participants(deps.as_ref(), participants)?;
+    if let Some(allowlist) = pool.allowlist.as_mut() {
+        allowlist.retain(|addr| !removed.contains(addr));
+    }
+    pools().save(deps.storage, pool_id, &pool)?;
+
+    Ok(Response::new()
+        .add_event(evt_update_allowlist(pool_id, "remove", &removed))
+        .add_attribute("action", "remove_participants"))
+}
+
+fn resolve_participants(
+    deps: Deps,
+    participants: Vec<Participant>,
+) -> Result<Vec<Addr>, ContractError> {
+    participants
+        .into_iter()
+        .map(|p| match p {
+            Participant::Address(addr) => Ok(deps.api.addr_validate(&addr)?),
+            Participant::Alias(username) => resolve_alias(deps, username),
+        })
+        .collect()
+}
+
+fn resolve_alias(deps: Deps, username: String) -> Result<Addr, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let alias_contract = cfg.alias_contract.ok_or(AliasNotConfigured)?;
+    let resp: AliasResolveResp = deps
+        .querier
+        .query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: alias_contract.to_string(),
+            msg: to_json_binary(&AliasQueryMsg::Resolve {
+                username: username.clone(),
+            })?,
+        }))
+        .map_err(|_| AliasNotFound { username })?;
+    Ok(deps.api.addr_validate(&resp.address)?)
+}
+
+/// Appends a deposit to the contributor's entry log. Indexes continue after
+/// any earlier, refunded entries.
+fn record_entry(
+    storage: &mut dyn Storage,
+    pool_id: u64,
+    contributor: &Addr,
+    entry: &ContributionEntry,
+) -> StdResult<()> {
+    let next = CONTRIBUTION_ENTRIES
+        .prefix((pool_id, contributor.clone()))
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(0, |last| last + 1);
+    CONTRIBUTION_ENTRIES.save(storage, (pool_id, contributor.clone(), next), entry)
+}
+
+/// Settings of a new ROSCA pool.
+struct RoscaTerms {
+    members: Vec<String>,
+    contribution: Coin,
+    period_secs: u64,
+    start_ts: u64,
+    randomize_order: bool,
+    late_penalty_bps: u16,
+    memo: Option<String>,
+}
+
+fn exec_create_rosca(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    terms: RoscaTerms,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    ensure!(
+        terms.contribution.denom == cfg.default_denom,
+        InvalidContribution
+    );
+    ensure!(!terms.contribution.amount.is_zero(), InvalidContribution);
+    ensure!(
+        terms.period_secs > 0 && terms.start_ts >= env.block.time.seconds(),
+        InvalidRosca
+    );
+    validate_fee_bps(terms.late_penalty_bps).map_err(|_| InvalidRosca)?;
+
+    let mut order: Vec<Addr> = Vec::with_capacity(terms.members.len());
+    for member in terms.members {
+        let addr = deps.api.addr_validate(&member)?;
+        ensure!(!order.contains(&addr), InvalidMembers);
+        order.push(addr);
+    }
+    ensure!(
+        order.len() >= 2 && order.len() <= cfg.max_participants as usize,
+        InvalidMembers
+    );
+
+    let mut id = NEXT_POOL_ID.load(deps.storage)?;
+    if terms.randomize_order {
+        shuffle(&mut order, &env, id);
+    }
+
+    let count = order.len() as u32;
+    let rosca = Rosca {
+        contribution: terms.contribution.amount,
+        period_secs: terms.period_secs,
+        start_ts: terms.start_ts,
+        late_penalty_bps: terms.late_penalty_bps,
+        rounds: order
+            .iter()
+            .map(|recipient| RoscaRound {
+                recipient: recipient.clone(),
+                collected: Uint128::zero(),
+                paid_count: 0,
+                paid_out: false,
+            })
+            .collect(),
+        current_round: 0,
+    };
+    let pool = Pool {
+        id,
+        creator: info.sender.clone(),
+        target: Coin::new(
+            (terms.contribution.amount * Uint128::from(count)).u128(),
+            terms.contribution.denom.clone(),
+        ),
+        current: Uint128::zero(),
+        participants: vec![],
+        max_participants: count,
+        allowlist: Some(order.clone()),
+        beneficiaries: vec![],
+        min_contribution: None,
+        max_contribution_per_address: None,
+        hard_cap: None,
+        memo: terms.memo,
+        created_at: env.block.time.seconds(),
+        expiry_ts: Some(rosca.due_ts(count - 1)),
+        distributed: false,
+        cancelled: false,
+        kind: PoolKind::Rosca(rosca),
+        tiers: vec![],
+    };
+
+    pools().save(deps.storage, id, &pool)?;
+    id += 1;
+    NEXT_POOL_ID.save(deps.storage, &id)?;
+
+    Ok(Response::new()
+        .add_event(evt_create_rosca(
+            pool.id,
+            pool.creator.as_str(),
+            &terms.contribution,
+            &order,
+            terms.start_ts,
+            terms.period_secs,
+        ))
+        .add_attribute("action", "create_rosca"))
+}
+
+/// Shuffles the payout order with a xorshift seeded from the block. This is
+/// only meant to avoid a hand-picked order, it is not unpredictable.
+fn shuffle(order: &mut [Addr], env: &Env, pool_id: u64) {
+    let mut seed = env.block.time.nanos() ^ env.block.height.rotate_left(32) ^ pool_id;
+    seed |= 1;
+    for i in (1..order.len()).rev() {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        order.swap(i, (seed % (i as u64 + 1)) as usize);
+    }
+}
+
+/// Pays the member's next unpaid round. Payments after the round's due time
+/// carry the late penalty, which is added to that round's pot.
+fn exec_contribute_rosca(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mut pool: Pool,
+    amount: Coin,
+) -> Result<Response, ContractError> {
+    let PoolKind::Rosca(rosca) = &mut pool.kind else {
+        return Err(WrongPoolKind);
+    };
+    let members = pool.allowlist.clone().unwrap_or_default();
+    ensure!(members.contains(&info.sender), NotAllowlisted);
+
+    let key = (pool.id, info.sender.clone());
+    let mut contribution = CONTRIBUTIONS
+        .may_load(deps.storage, key.clone())?
+        .unwrap_or_else(|| Contribution {
+            contributor: info.sender.clone(),
+            amount: Uint128::zero(),
+            contributed_at: 0,
+            rounds_paid: 0,
+            tier: None,
+        });
+
+    // Rounds are paid strictly in order
+    let round = contribution.rounds_paid;
+    ensure!(
+        (round as usize) < rosca.rounds.len(),
+        PoolAlreadyDistributed
+    );
+    let now = env.block.time.seconds();
+    ensure!(
+        now >= rosca.due_ts(round) - rosca.period_secs,
+        RoundNotStarted { round }
+    );
+
+    let penalty = if now > rosca.due_ts(round) {
+        calculate_fee(rosca.contribution, rosca.late_penalty_bps)
+    } else {
+        Uint128::zero()
+    };
+    let due = rosca.contribution + penalty;
+    ensure!(amount.denom == pool.target.denom, InvalidContribution);
+    ensure!(amount.amount == due, InvalidContribution);
+    ensure!(info.funds == [amount.clone()], FundsMismatch);
+
+    contribution.amount += due;
+    contribution.contributed_at = now;
+    contribution.rounds_paid += 1;
+    record_entry(
+        deps.storage,
+        pool.id,
+        &info.sender,
+        &ContributionEntry {
+            amount: due,
+            contributed_at: now,
+            round: Some(round),
+        },
+    )?;
+    CONTRIBUTIONS.save(deps.storage, key, &contribution)?;
+
+    let slot = &mut rosca.rounds[round as usize];
+    slot.collected += due;
+    slot.paid_count += 1;
+
+    if !pool.participants.contains(&info.sender) {
+        pool.participants.push(info.sender.clone());
+        PARTICIPANT_POOLS.save(deps.storage, (info.sender.clone(), pool.id), &Empty {})?;
+    }
+    pool.current += due;
+    pools().save(deps.storage, pool.id, &pool)?;
+
+    Ok(Response::new()
+        .add_event(evt_rosca_contribute(
+            pool.id,
+            round,
+            info.sender.as_str(),
+            &due,
+            &penalty,
+            &amount.denom,
+        ))
+        .add_attribute("action", "contribute"))
+}
+
+/// Pays the current round's pot to its recipient once every member has paid in.
+/// Anyone may trigger it since the recipient is fixed.
+fn exec_payout_rosca_round(deps: DepsMut, mut pool: Pool) -> Result<Response, ContractError> {
+    let PoolKind::Rosca(rosca) = &mut pool.kind else {
+        return Err(WrongPoolKind);
+    };
+    let members = rosca.rounds.len() as u32;
+    let round = rosca.current_round;
+    let slot = &mut rosca.rounds[round as usize];
+    ensure!(slot.paid_count == members, RoundIncomplete { round });
+
+    slot.paid_out = true;
+    let recipient = slot.recipient.clone();
+    let amount = slot.collected;
+    rosca.current_round += 1;
+    if rosca.current_round == members {
+        pool.distributed = true;
+    }
+    pool.current -= amount;
+    pools().save(deps.storage, pool.id, &pool)?;
+
+    let bank = BankMsg::Send {
+        to_address: recipient.to_string(),
+        amount: vec![Coin::new(amount.u128(), pool.target.denom.clone())],
+    };
+
+    Ok(Response::new()
+        .add_message(bank)
+        .add_event(evt_rosca_payout(
+            pool.id,
+            round,
+            recipient.as_str(),
+            &amount,
+            &pool.target.denom,
+        ))
+        .add_attribute("action", "distribute"))
+}
+
+fn exec_create_expense_group(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    members: Vec<String>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    let mut addrs = vec![info.sender.clone()];
+    for member in members {
+        let addr = deps.api.addr_validate(&member)?;
+        if !addrs.contains(&addr) {
+            addrs.push(addr);
+        }
+    }
+    ensure!(
+        addrs.len() >= 2 && addrs.len() <= cfg.max_participants as usize,
+        InvalidMembers
+    );
+
+    let id = NEXT_EXPENSE_GROUP_ID.load(deps.storage)?;
+    let group = ExpenseGroup {
+        id,
+        creator: info.sender.clone(),
+        members: addrs,
+        denom: cfg.default_denom,
+        created_at: env.block.time.seconds(),
+    };
+    EXPENSE_GROUPS.save(deps.storage, id, &group)?;
+    NEXT_EXPENSE_GROUP_ID.save(deps.storage, &(id + 1))?;
+
+    Ok(Response::new()
+        .add_event(evt_create_expense_group(
+            id,
+            group.creator.as_str(),
+            &group.members,
+            &group.denom,
+        ))
+        .add_attribute("action", "create_expense_group"))
+}
+
+fn exec_record_expense(
+    deps: DepsMut,
+    info: MessageInfo,
+    group_id: u64,
+    payer: String,
+    amount: Uint128,
+    split: ExpenseSplit,
+    memo: Option<String>,
+) -> Result<Response, ContractError> {
+    let group = EXPENSE_GROUPS
+        .load(deps.storage, group_id)
+        .map_err(|_| ExpenseGroupNotFound)?;
+    ensure!(group.members.contains(&info.sender), NotGroupMember);
+
+    // Nobody can put expenses, and so debts, in another member's name
+    let payer = deps.api.addr_validate(&payer)?;
+    ensure!(payer == info.sender, Unauthorized);
+    ensure!(!amount.is_zero(), InvalidExpenseSplit);
+
+    let shares = split_expense(deps.as_ref(), &group, amount, split)?;
+
+    // The payer is owed the full amount, each member owes their share
+    add_balance(deps.storage, group_id, &payer, signed(amount)?)?;
+    for (member, share) in &shares {
+        add_balance(deps.storage, group_id, member, -signed(*share)?)?;
+    }
+
+    Ok(Response::new()
+        .add_event(evt_record_expense(
+            group_id,
+            payer.as_str(),
+            &amount,
+            &shares,
+            memo.as_deref(),
+        ))
+        .add_attribute("action", "record_expense"))
+}
+
+fn exec_settle_up(
+    deps: DepsMut,
+    info: MessageInfo,
+    group_id: u64,
+) -> Result<Response, ContractError> {
+    let group = EXPENSE_GROUPS
+        .load(deps.storage, group_id)
+        .map_err(|_| ExpenseGroupNotFound)?;
+    ensure!(group.members.contains(&info.sender), NotGroupMember);
+
+    let payments: Vec<(Addr, Uint128)> = settlement_plan(deps.storage, &group)?
+        .into_iter()
+        .filter(|(from, _, _)| *from == info.sender)
+        .map(|(_, to, amount)| (to, amount))
+        .collect();
+    ensure!(!payments.is_empty(), NothingToSettle);
+
+    // The debtor pays their whole part of the plan at once
+    let total: Uint128 = payments.iter().map(|(_, amount)| *amount).sum();
+    ensure!(
+        info.funds == [Coin::new(total.u128(), group.denom.clone())],
+        FundsMismatch
+    );
+
+    add_balance(deps.storage, group_id, &info.sender, signed(total)?)?;
+    let mut msgs = Vec::with_capacity(payments.len());
+    for (to, amount) in &payments {
+        add_balance(deps.storage, group_id, to, -signed(*amount)?)?;
+        msgs.push(BankMsg::Send {
+            to_address: to.to_string(),
+            amount: vec![Coin::new(amount.u128(), group.denom.clone())],
+        });
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_event(evt_settle_up(
+            group_id,
+            info.sender.as_str(),
+            &payments,
+            &group.denom,
+        ))
+        .add_attribute("action", "settle_up"))
+}
+
+/// Resolves `split` into each member's share of `amount`. Rounding dust goes to
+/// the first listed member so the shares always add up to `amount`.
+fn split_expense(
+    deps: Deps,
+    group: &ExpenseGroup,
+    amount: Uint128,
+    split: ExpenseSplit,
+) -> Result<Vec<(Addr, Uint128)>, ContractError> {
+    let validate_member = |addr: &str, seen: &[Addr]| -> Result<Addr, ContractError> {
+        let addr = deps.api.addr_validate(addr)?;
+        ensure!(group.members.contains(&addr), NotGroupMember);
+        ensure!(!seen.contains(&addr), InvalidExpenseSplit);
+        Ok(addr)
+    };
+
+    match split {
+        ExpenseSplit::Equal {} => {
+            let count = Uint128::from(group.members.len() as u128);
+            let share = amount / count;
+            let mut dust = (amount % count).u128();
+            Ok(group
+                .members
+                .iter()
+                .map(|member| {
+                    let extra = if dust > 0 { 1 } else { 0 };
+                    dust -= extra;
+                    (member.clone(), share + Uint128::new(extra))
+                })
+                .collect())
+        }
+        ExpenseSplit::Exact(entries) => {
+            ensure!(!entries.is_empty(), InvalidExpenseSplit);
+            let mut shares: Vec<(Addr, Uint128)> = Vec::with_capacity(entries.len());
+            for (member, share) in entries {
+                let seen: Vec<Addr> = shares.iter().map(|(a, _)| a.clone()).collect();
+                shares.push((validate_member(&member, &seen)?, share));
+            }
+            let total: Uint128 = shares.iter().map(|(_, share)| *share).sum();
+            ensure!(total == amount, InvalidExpenseSplit);
+            Ok(shares)
+        }
+        ExpenseSplit::Percent(entries) => {
+            let weights: Vec<u16> = entries.iter().map(|(_, w)| *w).collect();
+            ensure!(
+                weights.iter().all(|w| *w > 0) && validate_allocation_weights(&weights),
+                InvalidExpenseSplit
+            );
+            let mut weighted: Vec<(Addr, u16)> = Vec::with_capacity(entries.len());
+            for (member, weight) in entries {
+                let seen: Vec<Addr> = weighted.iter().map(|(a, _)| a.clone()).collect();
+                weighted.push((validate_member(&member, &seen)?, weight));
+            }
+            Ok(split_payouts(amount, &weighted))
+        }
+    }
+}
+
+/// Greedily matches the largest debtor with the largest creditor until every
+/// balance is zero, which keeps the number of transfers small.
+fn settlement_plan(
+    storage: &dyn Storage,
+    group: &ExpenseGroup,
+) -> StdResult<Vec<(Addr, Addr, Uint128)>> {
+    let mut debtors = vec![];
+    let mut creditors = vec![];
+    for member in &group.members {
+        let net = EXPENSE_BALANCES
+            .may_load(storage, (group.id, member.clone()))?
+            .unwrap_or_default();
+        let abs = Uint128::new(net.i128().unsigned_abs());
+        if net.is_negative() {
+            debtors.push((member.clone(), abs));
+        } else if !net.is_zero() {
+            creditors.push((member.clone(), abs));
+        }
+    }
+    // Largest first, ties in member order, so the plan is deterministic
+    debtors.sort_by_key(|(_, amount)| Reverse(*amount));
+    creditors.sort_by_key(|(_, amount)| Reverse(*amount));
+
+    let mut plan = vec![];
+    let (mut d, mut c) = (0, 0);
+    while d < debtors.len() && c < creditors.len() {
+        let amount = debtors[d].1.min(creditors[c].1);
+        plan.push((debtors[d].0.clone(), creditors[c].0.clone(), amount));
+        debtors[d].1 -= amount;
+        creditors[c].1 -= amount;
+        if debtors[d].1.is_zero() {
+            d += 1;
+        }
+        if creditors[c].1.is_zero() {
+            c += 1;
+        }
+    }
+    Ok(plan)
+}
+
+fn add_balance(
+    storage: &mut dyn Storage,
+    group_id: u64,
+    member: &Addr,
+    delta: Int128,
+) -> StdResult<()> {
+    EXPENSE_BALANCES.update(storage, (group_id, member.clone()), |bal| {
+        bal.unwrap_or_default()
+            .checked_add(delta)
+            .map_err(StdError::from)
+    })?;
+    Ok(())
+}
+
+fn signed(amount: Uint128) -> StdResult<Int128> {
+    i128::try_from(amount.u128())
+        .map(Int128::new)
+        .map_err(|_| StdError::generic_err("amount too large"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => {
@@ -297,41 +1358,30 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
                 admin: c.admin.to_string(),
                 default_denom: c.default_denom,
                 max_participants: c.max_participants,
+                alias_contract: c.alias_contract.map(|a| a.to_string()),
             })
         }
         QueryMsg::GetPool { id } => {
-            let p = POOLS.load(deps.storage, id)?;
-            to_json_binary(&PoolResp {
-                id: p.id,
-                creator: p.creator.to_string(),
-                target: p.target,
-                current: p.current.to_string(),
-                participants: p.participants.iter().map(|addr| addr.to_string()).collect(),
-                memo: p.memo,
-                created_at: p.created_at,
-                expiry_ts: p.expiry_ts,
-                distributed: p.distributed,
-                cancelled: p.cancelled,
-            })
+            let p = pools().load(deps.storage, id)?;
+            to_json_binary(&pool_resp(p))
         }
         QueryMsg::ListContributions {
             pool_id,
             start_after,
             limit,
         } => {
-            let start = if let Some(addr) = start_after {
-                let validated_addr = deps.api.addr_validate(&addr)?;
-                Some(Bound::exclusive((pool_id, validated_addr)))
-            } else {
-                None
+            let start = match start_after {
+                Some(addr) => Some(Bound::exclusive(deps.api.addr_validate(&addr)?)),
+                None => None,
             };
             let limit = limit.unwrap_or(30) as usize;
 
             let contributions: StdResult<Vec<_>> = CONTRIBUTIONS
+                .prefix(pool_id)
                 .range(deps.storage, start, None, Order::Ascending)
                 .take(limit)
                 .map(|item| {
-                    let ((_, addr), contrib) = item?;
+                    let (addr, contrib) = item?;
                     Ok(ContributionResp {
                         contributor: addr.to_string(),
                         amount: contrib.amount.to_string(),
@@ -342,31 +1392,262 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
 
             to_json_binary(&contributions?)
         }
-        QueryMsg::ListPools { start_after, limit } => {
-            let start = start_after.map(|id| Bound::exclusive(id));
+        QueryMsg::ListPoolsByCreator {
+            creator,
+            start_after,
+            limit,
+        } => {
+            let creator = deps.api.addr_validate(&creator)?;
+            let start = start_after.map(Bound::exclusive);
             let limit = limit.unwrap_or(30) as usize;
 
-            let pools: StdResult<Vec<_>> = POOLS
+            let pools: StdResult<Vec<_>> = pools()
+                .idx
+                .creator
+                .prefix(creator)
                 .range(deps.storage, start, None, Order::Ascending)
                 .take(limit)
-                .map(|item| {
-                    let (id, p) = item?;
-                    Ok(PoolResp {
-                        id,
-                        creator: p.creator.to_string(),
-                        target: p.target,
-                        current: p.current.to_string(),
-                        participants: p.participants.iter().map(|addr| addr.to_string()).collect(),
-                        memo: p.memo,
-                        created_at: p.created_at,
-                        expiry_ts: p.expiry_ts,
-                        distributed: p.distributed,
-                        cancelled: p.cancelled,
+                .map(|item| item.map(|(_, p)| pool_resp(p)))
+                .collect();
+
+            to_json_binary(&pools?)
+        }
+        QueryMsg::ListPoolsByParticipant {
+            address,
+            start_after,
+            limit,
+        } => {
+            let addr = deps.api.addr_validate(&address)?;
+            let start = start_after.map(Bound::exclusive);
+            let limit = limit.unwrap_or(30) as usize;
+
+            let pools: StdResult<Vec<_>> = PARTICIPANT_POOLS
+                .prefix(addr)
+                .keys(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|id| pools().load(deps.storage, id?).map(pool_resp))
+                .collect();
+
+            to_json_binary(&pools?)
+        }
+        QueryMsg::GetContribution { pool_id, address } => {
+            let addr = deps.api.addr_validate(&address)?;
+            let c = CONTRIBUTIONS.load(deps.storage, (pool_id, addr.clone()))?;
+            let entry_count = CONTRIBUTION_ENTRIES
+                .prefix((pool_id, addr.clone()))
+                .keys(deps.storage, None, None, Order::Descending)
+                .next()
+                .transpose()?
+                .map_or(0, |last| last + 1);
+            to_json_binary(&ContributionDetailResp {
+                contributor: c.contributor.to_string(),
+                total: c.amount.to_string(),
+                tier: c.tier,
+                history: contribution_entries(deps, pool_id, addr, None, None)?,
+                entry_count,
+            })
+        }
+        QueryMsg::ListContributionEntries {
+            pool_id,
+            address,
+            start_after,
+            limit,
+        } => {
+            let addr = deps.api.addr_validate(&address)?;
+            to_json_binary(&contribution_entries(
+                deps,
+                pool_id,
+                addr,
+                start_after,
+                limit,
+            )?)
+        }
+        QueryMsg::ListTierBackers {
+            pool_id,
+            tier,
+            start_after,
+            limit,
+        } => {
+            let start = match start_after {
+                Some(addr) => Some(Bound::exclusive(deps.api.addr_validate(&addr)?)),
+                None => None,
+            };
+            let limit = limit.unwrap_or(10).min(30) as usize;
+
+            let backers: StdResult<Vec<_>> = TIER_BACKERS
+                .prefix((pool_id, tier))
+                .keys(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|addr| {
+                    let addr = addr?;
+                    let c = CONTRIBUTIONS.load(deps.storage, (pool_id, addr.clone()))?;
+                    Ok(ContributionResp {
+                        contributor: addr.to_string(),
+                        amount: c.amount.to_string(),
+                        contributed_at: c.contributed_at,
                     })
                 })
                 .collect();
 
+            to_json_binary(&backers?)
+        }
+        QueryMsg::RoscaSchedule { pool_id } => {
+            let p = pools().load(deps.storage, pool_id)?;
+            let PoolKind::Rosca(rosca) = p.kind else {
+                return Err(StdError::generic_err("Not a ROSCA pool"));
+            };
+            let mut paid: Vec<Vec<String>> = vec![vec![]; rosca.rounds.len()];
+            for member in p.allowlist.unwrap_or_default() {
+                let rounds_paid = CONTRIBUTIONS
+                    .may_load(deps.storage, (pool_id, member.clone()))?
+                    .map_or(0, |c| c.rounds_paid);
+                for round in paid.iter_mut().take(rounds_paid as usize) {
+                    round.push(member.to_string());
+                }
+            }
+            let rounds = rosca
+                .rounds
+                .iter()
+                .zip(paid)
+                .enumerate()
+                .map(|(i, (r, paid_members))| RoscaRoundResp {
+                    round: i as u32,
+                    recipient: r.recipient.to_string(),
+                    due_ts: rosca.due_ts(i as u32),
+                    collected: r.collected,
+                    paid_members,
+                    paid_out: r.paid_out,
+                })
+                .collect();
+            to_json_binary(&RoscaScheduleResp {
+                contribution: Coin::new(rosca.contribution.u128(), p.target.denom),
+                period_secs: rosca.period_secs,
+                start_ts: rosca.start_ts,
+                late_penalty_bps: rosca.late_penalty_bps,
+                current_round: rosca.current_round,
+                rounds,
+            })
+        }
+        QueryMsg::GetExpenseGroup { id } => {
+            let g = EXPENSE_GROUPS.load(deps.storage, id)?;
+            to_json_binary(&ExpenseGroupResp {
+                id: g.id,
+                creator: g.creator.to_string(),
+                members: g.members.iter().map(|addr| addr.to_string()).collect(),
+                denom: g.denom,
+                created_at: g.created_at,
+            })
+        }
+        QueryMsg::ExpenseBalances { group_id } => {
+            let g = EXPENSE_GROUPS.load(deps.storage, group_id)?;
+            let balances: StdResult<Vec<_>> = g
+                .members
+                .into_iter()
+                .map(|member| {
+                    let net = EXPENSE_BALANCES
+                        .may_load(deps.storage, (group_id, member.clone()))?
+                        .unwrap_or_default();
+                    Ok(MemberBalanceResp {
+                        address: member.to_string(),
+                        net,
+                    })
+                })
+                .collect();
+            to_json_binary(&balances?)
+        }
+        QueryMsg::SettlementPlan { group_id } => {
+            let g = EXPENSE_GROUPS.load(deps.storage, group_id)?;
+            let plan: Vec<_> = settlement_plan(deps.storage, &g)?
+                .into_iter()
+                .map(|(from, to, amount)| SettlementResp {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    amount,
+                })
+                .collect();
+            to_json_binary(&plan)
+        }
+        QueryMsg::ListPools { start_after, limit } => {
+            let start = start_after.map(Bound::exclusive);
+            let limit = limit.unwrap_or(30) as usize;
+
+            let pools: StdResult<Vec<_>> = pools()
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| item.map(|(_, p)| pool_resp(p)))
+                .collect();
+
             to_json_binary(&pools?)
         }
     }
 }
+
+fn contribution_entries(
+    deps: Deps,
+    pool_id: u64,
+    contributor: Addr,
+    start_after: Option<u32>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ContributionEntryResp>> {
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(10).min(30) as usize;
+
+    CONTRIBUTION_ENTRIES
+        .prefix((pool_id, contributor))
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (index, entry) = item?;
+            Ok(ContributionEntryResp {
+                index,
+                amount: entry.amount.to_string(),
+                contributed_at: entry.contributed_at,
+                round: entry.round,
+            })
+        })
+        .collect()
+}
+
+fn pool_resp(p: Pool) -> PoolResp {
+    PoolResp {
+        id: p.id,
+        creator: p.creator.to_string(),
+        target: p.target,
+        current: p.current.to_string(),
+        participants: p.participants.iter().map(|addr| addr.to_string()).collect(),
+        max_participants: p.max_participants,
+        allowlist: p
+            .allowlist
+            .map(|list| list.iter().map(|addr| addr.to_string()).collect()),
+        beneficiaries: p
+            .beneficiaries
+            .into_iter()
+            .map(|(addr, weight)| (addr.to_string(), weight))
+            .collect(),
+        min_contribution: p.min_contribution,
+        max_contribution_per_address: p.max_contribution_per_address,
+        hard_cap: p.hard_cap,
+        memo: p.memo,
+        created_at: p.created_at,
+        expiry_ts: p.expiry_ts,
+        distributed: p.distributed,
+        cancelled: p.cancelled,
+        kind: match p.kind {
+            PoolKind::Target => "target",
+            PoolKind::Rosca(_) => "rosca",
+        }
+        .to_string(),
+        tiers: p
+            .tiers
+            .into_iter()
+            .enumerate()
+            .map(|(i, t)| RewardTierResp {
+                tier: i as u32,
+                min_amount: t.min_amount,
+                description: t.description,
+                limit: t.limit,
+                backers: t.backers,
+            })
+            .collect(),
+    }
+}
@@ -27,6 +27,66 @@ pub enum ContractError {
     #[error("Invalid contribution amount")]
     InvalidContribution,
 
+    #[error("Attached funds must match the contribution amount")]
+    FundsMismatch,
+
+    #[error("Invalid max participants")]
+    InvalidMaxParticipants,
+
+    #[error("Invalid beneficiaries")]
+    InvalidBeneficiaries,
+
+    #[error("Invalid ROSCA parameters")]
+    InvalidRosca,
+
+    #[error("Not supported for this pool kind")]
+    WrongPoolKind,
+
+    #[error("Round {round} has not started")]
+    RoundNotStarted { round: u32 },
+
+    #[error("Round {round} is still waiting on contributions")]
+    RoundIncomplete { round: u32 },
+
+    #[error("Round {round} has not defaulted")]
+    RoundNotDefaulted { round: u32 },
+
+    #[error("Expense group not found")]
+    ExpenseGroupNotFound,
+
+    #[error("Invalid expense group members")]
+    InvalidMembers,
+
+    #[error("Not a member of the expense group")]
+    NotGroupMember,
+
+    #[error("Invalid expense split")]
+    InvalidExpenseSplit,
+
+    #[error("Nothing to settle")]
+    NothingToSettle,
+
+    #[error("Not on the pool allowlist")]
+    NotAllowlisted,
+
+    #[error("Alias contract not configured")]
+    AliasNotConfigured,
+
+    #[error("Alias not found: {username}")]
+    AliasNotFound { username: String },
+
+    #[error("Invalid contribution limits")]
+    InvalidContributionLimits,
+
+    #[error("Contribution below the pool minimum")]
+    BelowMinContribution,
+
+    #[error("Contribution exceeds the per-address maximum")]
+    ContributionLimitExceeded,
+
+    #[error("Invalid reward tier")]
+    InvalidRewardTier,
+
     #[error("Pool is full")]
     PoolFull,
 
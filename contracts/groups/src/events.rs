@@ -1,4 +1,4 @@
-use cosmwasm_std::{Event, Coin, Uint128};
+use cosmwasm_std::{Addr, Event, Coin, Uint128};
 
 pub fn evt_create_pool(id: u64, creator: &str, target: &Coin, memo: Option<&str>) -> Event {
     let mut event = Event::new("seimoney.create_pool")
@@ -22,12 +22,52 @@ pub fn evt_contribute(pool_id: u64, contributor: &str, amount: &Uint128, denom:
         .add_attribute("denom", denom.to_string())
 }
 
-pub fn evt_distribute(pool_id: u64, creator: &str, total_amount: &Uint128, denom: &str) -> Event {
+pub fn evt_add_reward_tier(pool_id: u64, tier: u32, min_amount: &Uint128, limit: Option<u32>) -> Event {
+    let mut event = Event::new("seimoney.add_reward_tier")
+        .add_attribute("pool_id", pool_id.to_string())
+        .add_attribute("tier", tier.to_string())
+        .add_attribute("min_amount", min_amount.to_string());
+
+    if let Some(limit) = limit {
+        event = event.add_attribute("limit", limit.to_string());
+    }
+
+    event
+}
+
+pub fn evt_claim_tier(pool_id: u64, tier: u32, contributor: &str, previous: Option<u32>) -> Event {
+    let mut event = Event::new("seimoney.claim_tier")
+        .add_attribute("pool_id", pool_id.to_string())
+        .add_attribute("tier", tier.to_string())
+        .add_attribute("contributor", contributor.to_string());
+
+    if let Some(previous) = previous {
+        event = event.add_attribute("previous_tier", previous.to_string());
+    }
+
+    event
+}
+
+pub fn evt_distribute(
+    pool_id: u64,
+    creator: &str,
+    total_amount: &Uint128,
+    denom: &str,
+    payouts: &[(Addr, Uint128)],
+) -> Event {
     Event::new("seimoney.distribute")
         .add_attribute("pool_id", pool_id.to_string())
         .add_attribute("creator", creator.to_string())
         .add_attribute("total_amount", total_amount.to_string())
         .add_attribute("denom", denom.to_string())
+        .add_attribute(
+            "payouts",
+            payouts
+                .iter()
+                .map(|(addr, amount)| format!("{}:{}", addr, amount))
+                .collect::<Vec<_>>()
+                .join(","),
+        )
 }
 
 pub fn evt_cancel_pool(pool_id: u64, creator: &str) -> Event {
@@ -36,6 +76,12 @@ pub fn evt_cancel_pool(pool_id: u64, creator: &str) -> Event {
         .add_attribute("creator", creator.to_string())
 }
 
+pub fn evt_pool_failed(pool_id: u64, creator: &str) -> Event {
+    Event::new("seimoney.pool_failed")
+        .add_attribute("pool_id", pool_id.to_string())
+        .add_attribute("creator", creator.to_string())
+}
+
 pub fn evt_refund_contribution(pool_id: u64, contributor: &str, amount: &Uint128, denom: &str) -> Event {
     Event::new("seimoney.refund_contribution")
         .add_attribute("pool_id", pool_id.to_string())
@@ -43,3 +89,95 @@ pub fn evt_refund_contribution(pool_id: u64, contributor: &str, amount: &Uint128
         .add_attribute("amount", amount.to_string())
         .add_attribute("denom", denom.to_string())
 }
+
+pub fn evt_update_allowlist(pool_id: u64, op: &str, addrs: &[Addr]) -> Event {
+    Event::new("seimoney.update_allowlist")
+        .add_attribute("pool_id", pool_id.to_string())
+        .add_attribute("op", op.to_string())
+        .add_attribute(
+            "participants",
+            addrs.iter().map(|a| a.as_str()).collect::<Vec<_>>().join(","),
+        )
+}
+
+pub fn evt_create_expense_group(id: u64, creator: &str, members: &[Addr], denom: &str) -> Event {
+    Event::new("seimoney.create_expense_group")
+        .add_attribute("id", id.to_string())
+        .add_attribute("creator", creator.to_string())
+        .add_attribute(
+            "members",
+            members.iter().map(|a| a.as_str()).collect::<Vec<_>>().join(","),
+        )
+        .add_attribute("denom", denom.to_string())
+}
+
+pub fn evt_record_expense(
+    group_id: u64,
+    payer: &str,
+    amount: &Uint128,
+    shares: &[(Addr, Uint128)],
+    memo: Option<&str>,
+) -> Event {
+    let mut event = Event::new("seimoney.record_expense")
+        .add_attribute("group_id", group_id.to_string())
+        .add_attribute("payer", payer.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute(
+            "shares",
+            shares
+                .iter()
+                .map(|(addr, amount)| format!("{}:{}", addr, amount))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+
+    if let Some(memo) = memo {
+        event = event.add_attribute("memo", memo.to_string());
+    }
+
+    event
+}
+
+pub fn evt_settle_up(group_id: u64, debtor: &str, payments: &[(Addr, Uint128)], denom: &str) -> Event {
+    Event::new("seimoney.settle_up")
+        .add_attribute("group_id", group_id.to_string())
+        .add_attribute("debtor", debtor.to_string())
+        .add_attribute(
+            "payments",
+            payments
+                .iter()
+                .map(|(addr, amount)| format!("{}:{}", addr, amount))
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+        .add_attribute("denom", denom.to_string())
+}
+
+pub fn evt_create_rosca(id: u64, creator: &str, contribution: &Coin, order: &[Addr], start_ts: u64, period_secs: u64) -> Event {
+    Event::new("seimoney.create_rosca")
+        .add_attribute("id", id.to_string())
+        .add_attribute("creator", creator.to_string())
+        .add_attribute("contribution", format!("{}{}", contribution.amount, contribution.denom))
+        .add_attribute("order", order.iter().map(|a| a.as_str()).collect::<Vec<_>>().join(","))
+        .add_attribute("start_ts", start_ts.to_string())
+        .add_attribute("period_secs", period_secs.to_string())
+}
+
+pub fn evt_rosca_contribute(pool_id: u64, round: u32, contributor: &str, amount: &Uint128, penalty: &Uint128, denom: &str) -> Event {
+    Event::new("seimoney.rosca_contribute")
+        .add_attribute("pool_id", pool_id.to_string())
+        .add_attribute("round", round.to_string())
+        .add_attribute("contributor", contributor.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("penalty", penalty.to_string())
+        .add_attribute("denom", denom.to_string())
+}
+
+pub fn evt_rosca_payout(pool_id: u64, round: u32, recipient: &str, amount: &Uint128, denom: &str) -> Event {
+    Event::new("seimoney.rosca_payout")
+        .add_attribute("pool_id", pool_id.to_string())
+        .add_attribute("round", round.to_string())
+        .add_attribute("recipient", recipient.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("denom", denom.to_string())
+}
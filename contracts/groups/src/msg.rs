@@ -1,4 +1,4 @@
-use cosmwasm_std::Coin;
+use cosmwasm_std::{Coin, Int128, Uint128};
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
 #[cw_serde]
@@ -6,15 +6,37 @@ pub struct InstantiateMsg {
     pub admin: Option<String>,
     pub default_denom: String,
     pub max_participants: Option<u32>,
+    /// `seimoney-alias` contract used to resolve `Participant::Alias`.
+    pub alias_contract: Option<String>,
+}
+
+#[cw_serde]
+pub enum Participant {
+    Address(String),
+    /// Username registered in the configured alias contract.
+    Alias(String),
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
     CreatePool {
         target: Coin,
+        /// Defaults to, and may not exceed, the contract-wide `max_participants`.
         max_participants: Option<u32>,
         memo: Option<String>,
         expiry_ts: Option<u64>,
+        /// If set, only these participants may contribute.
+        allowlist: Option<Vec<Participant>>,
+        /// `(address, weight_bps)` pairs the pool is paid out to, weights summing
+        /// to 10000. Defaults to the creator.
+        beneficiaries: Option<Vec<(String, u16)>>,
+        /// Smallest amount accepted per contribution.
+        min_contribution: Option<Uint128>,
+        /// Most a single address may contribute in total.
+        max_contribution_per_address: Option<Uint128>,
+        /// Lets contributions continue past `target` up to this amount until
+        /// `expiry_ts`, which is then required.
+        hard_cap: Option<Uint128>,
     },
     Contribute {
         pool_id: u64,
@@ -23,12 +45,77 @@ pub enum ExecuteMsg {
     Distribute {
         pool_id: u64,
     },
+    /// Creator-only for target pools. A ROSCA can be cancelled by anyone once
+    /// its current round is a full period overdue, refunding that round.
     CancelPool {
         pool_id: u64,
     },
     RefundContribution {
         pool_id: u64,
     },
+    /// Creator only: adds a reward tier for contributors whose total reaches `min_amount`.
+    AddRewardTier {
+        pool_id: u64,
+        min_amount: Uint128,
+        description: String,
+        limit: Option<u32>,
+    },
+    /// Refunds contributors of pools that expired under target, touching at most
+    /// `limit` pools and contributors. Anyone may call it.
+    ProcessExpired {
+        limit: Option<u32>,
+    },
+    /// Creator only: adds to the pool's allowlist, restricting an open pool.
+    AddParticipants {
+        pool_id: u64,
+        participants: Vec<Participant>,
+    },
+    /// Creator only: removes from the pool's allowlist.
+    RemoveParticipants {
+        pool_id: u64,
+        participants: Vec<Participant>,
+    },
+    /// Starts a rotating savings club: each round every member contributes
+    /// `contribution` and one member receives the whole pot.
+    CreateRosca {
+        /// Payout order, unless `randomize_order` is set.
+        members: Vec<String>,
+        contribution: Coin,
+        period_secs: u64,
+        start_ts: u64,
+        randomize_order: bool,
+        /// Extra charged on late payments and added to that round's pot (max 1000).
+        late_penalty_bps: u16,
+        memo: Option<String>,
+    },
+    /// Starts a shared expense ledger in the default denom. The sender is always a member.
+    CreateExpenseGroup {
+        members: Vec<String>,
+    },
+    /// Members only: records that `payer` paid `amount` on behalf of the group.
+    /// Members can only record what they paid themselves, so `payer` must be the sender.
+    RecordExpense {
+        group_id: u64,
+        payer: String,
+        amount: Uint128,
+        split: ExpenseSplit,
+        memo: Option<String>,
+    },
+    /// Pays the sender's part of the settlement plan from the attached funds.
+    SettleUp {
+        group_id: u64,
+    },
+}
+
+/// How an expense is shared between members.
+#[cw_serde]
+pub enum ExpenseSplit {
+    /// Evenly between all members.
+    Equal {},
+    /// Exact `(member, amount)` shares summing to the expense amount.
+    Exact(Vec<(String, Uint128)>),
+    /// `(member, weight_bps)` shares summing to 10000.
+    Percent(Vec<(String, u16)>),
 }
 
 #[cw_serde]
@@ -40,8 +127,43 @@ pub enum QueryMsg {
     GetPool { id: u64 },
     #[returns(Vec<ContributionResp>)]
     ListContributions { pool_id: u64, start_after: Option<String>, limit: Option<u32> },
+    /// Total and the first page of deposit history; the rest is paged with
+    /// `ListContributionEntries`.
+    #[returns(ContributionDetailResp)]
+    GetContribution { pool_id: u64, address: String },
+    /// Individual deposits of `address` into the pool, oldest first.
+    #[returns(Vec<ContributionEntryResp>)]
+    ListContributionEntries {
+        pool_id: u64,
+        address: String,
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    },
     #[returns(Vec<PoolResp>)]
     ListPools { start_after: Option<u64>, limit: Option<u32> },
+    #[returns(Vec<PoolResp>)]
+    ListPoolsByCreator { creator: String, start_after: Option<u64>, limit: Option<u32> },
+    /// Pools `address` has contributed to.
+    #[returns(Vec<PoolResp>)]
+    ListPoolsByParticipant { address: String, start_after: Option<u64>, limit: Option<u32> },
+    /// Contributors currently in reward tier `tier` of the pool.
+    #[returns(Vec<ContributionResp>)]
+    ListTierBackers {
+        pool_id: u64,
+        tier: u32,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(RoscaScheduleResp)]
+    RoscaSchedule { pool_id: u64 },
+    #[returns(ExpenseGroupResp)]
+    GetExpenseGroup { id: u64 },
+    /// Net balance of every member of the group.
+    #[returns(Vec<MemberBalanceResp>)]
+    ExpenseBalances { group_id: u64 },
+    /// Transfers that would clear all debts in the group.
+    #[returns(Vec<SettlementResp>)]
+    SettlementPlan { group_id: u64 },
 }
 
 #[cw_serde]
@@ -49,6 +171,7 @@ pub struct ConfigResp {
     pub admin: String,
     pub default_denom: String,
     pub max_participants: u32,
+    pub alias_contract: Option<String>,
 }
 
 #[cw_serde]
@@ -58,11 +181,29 @@ pub struct PoolResp {
     pub target: Coin,
     pub current: String,
     pub participants: Vec<String>,
+    pub max_participants: u32,
+    pub allowlist: Option<Vec<String>>,
+    pub beneficiaries: Vec<(String, u16)>,
+    pub min_contribution: Option<Uint128>,
+    pub max_contribution_per_address: Option<Uint128>,
+    pub hard_cap: Option<Uint128>,
     pub memo: Option<String>,
     pub created_at: u64,
     pub expiry_ts: Option<u64>,
     pub distributed: bool,
     pub cancelled: bool,
+    /// `"target"` or `"rosca"`.
+    pub kind: String,
+    pub tiers: Vec<RewardTierResp>,
+}
+
+#[cw_serde]
+pub struct RewardTierResp {
+    pub tier: u32,
+    pub min_amount: Uint128,
+    pub description: String,
+    pub limit: Option<u32>,
+    pub backers: u32,
 }
 
 #[cw_serde]
@@ -71,3 +212,79 @@ pub struct ContributionResp {
     pub amount: String,
     pub contributed_at: u64,
 }
+
+#[cw_serde]
+pub struct ContributionDetailResp {
+    pub contributor: String,
+    pub total: String,
+    pub tier: Option<u32>,
+    /// Oldest deposits first, at most one page of `ListContributionEntries`.
+    pub history: Vec<ContributionEntryResp>,
+    /// Number of deposits recorded, including those not in `history`.
+    pub entry_count: u32,
+}
+
+#[cw_serde]
+pub struct ContributionEntryResp {
+    pub index: u32,
+    pub amount: String,
+    pub contributed_at: u64,
+    pub round: Option<u32>,
+}
+
+#[cw_serde]
+pub struct RoscaScheduleResp {
+    pub contribution: Coin,
+    pub period_secs: u64,
+    pub start_ts: u64,
+    pub late_penalty_bps: u16,
+    pub current_round: u32,
+    pub rounds: Vec<RoscaRoundResp>,
+}
+
+#[cw_serde]
+pub struct RoscaRoundResp {
+    pub round: u32,
+    pub recipient: String,
+    pub due_ts: u64,
+    pub collected: Uint128,
+    /// Members who have paid in for this round.
+    pub paid_members: Vec<String>,
+    pub paid_out: bool,
+}
+
+#[cw_serde]
+pub struct ExpenseGroupResp {
+    pub id: u64,
+    pub creator: String,
+    pub members: Vec<String>,
+    pub denom: String,
+    pub created_at: u64,
+}
+
+#[cw_serde]
+pub struct MemberBalanceResp {
+    pub address: String,
+    /// Positive when the member is owed money, negative when they owe.
+    pub net: Int128,
+}
+
+#[cw_serde]
+pub struct SettlementResp {
+    pub from: String,
+    pub to: String,
+    pub amount: Uint128,
+}
+
+/// Subset of the `seimoney-alias` query interface used to resolve usernames.
+#[cw_serde]
+pub enum AliasQueryMsg {
+    Resolve { username: String },
+}
+
+#[cw_serde]
+pub struct AliasResolveResp {
+    pub username: String,
+    pub address: String,
+    pub registered_at: u64,
+}
@@ -1,11 +1,13 @@
-use cosmwasm_std::{Addr, Coin, Uint128};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::Empty;
+use cosmwasm_std::{Addr, Coin, Int128, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
 pub struct Config {
     pub admin: Addr,
     pub default_denom: String,
     pub max_participants: u32,
+    pub alias_contract: Option<Addr>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
@@ -15,21 +17,150 @@ pub struct Pool {
     pub target: Coin,
     pub current: Uint128,
     pub participants: Vec<Addr>,
+    pub max_participants: u32,
+    /// Addresses allowed to contribute; `None` means anyone may.
+    pub allowlist: Option<Vec<Addr>>,
+    /// Payout split as `(address, weight_bps)`, weights summing to 10000.
+    pub beneficiaries: Vec<(Addr, u16)>,
+    pub min_contribution: Option<Uint128>,
+    pub max_contribution_per_address: Option<Uint128>,
+    /// Stretch goal above the soft `target`; contributions stop here.
+    pub hard_cap: Option<Uint128>,
     pub memo: Option<String>,
     pub created_at: u64,
     pub expiry_ts: Option<u64>,
     pub distributed: bool,
     pub cancelled: bool,
+    pub kind: PoolKind,
+    /// Crowdfunding reward tiers, referenced by index.
+    pub tiers: Vec<RewardTier>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+pub struct RewardTier {
+    /// Total contribution needed to qualify.
+    pub min_amount: Uint128,
+    pub description: String,
+    /// Maximum number of backers, if limited.
+    pub limit: Option<u32>,
+    pub backers: u32,
+}
+
+impl Pool {
+    /// Amount after which no more contributions are taken.
+    pub fn cap(&self) -> Uint128 {
+        self.hard_cap.unwrap_or(self.target.amount)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+pub enum PoolKind {
+    /// Collects towards `target` and pays out once to the beneficiaries.
+    Target,
+    /// Rotating savings club: every member pays in each round and one member takes the pot.
+    Rosca(Rosca),
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+pub struct Rosca {
+    /// Amount each member pays per round.
+    pub contribution: Uint128,
+    pub period_secs: u64,
+    pub start_ts: u64,
+    /// Penalty on payments made after a round's due time, in basis points.
+    pub late_penalty_bps: u16,
+    /// Round `i` is paid out to `rounds[i].recipient`.
+    pub rounds: Vec<RoscaRound>,
+    /// First round that has not been paid out yet.
+    pub current_round: u32,
+}
+
+impl Rosca {
+    /// Payments for `round` are late after this time.
+    pub fn due_ts(&self, round: u32) -> u64 {
+        self.start_ts + self.period_secs * (round as u64 + 1)
+    }
+
+    /// A round still short of payments a full period after it fell due has
+    /// defaulted, and anyone may cancel the club.
+    pub fn default_ts(&self, round: u32) -> u64 {
+        self.due_ts(round) + self.period_secs
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+pub struct RoscaRound {
+    pub recipient: Addr,
+    /// Contributions plus late penalties collected for the round.
+    pub collected: Uint128,
+    pub paid_count: u32,
+    pub paid_out: bool,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
 pub struct Contribution {
     pub contributor: Addr,
+    /// Running total across all of the contributor's deposits.
     pub amount: Uint128,
+    /// Time of the most recent deposit.
     pub contributed_at: u64,
+    /// ROSCA rounds the member has paid into, which is also the next round due.
+    #[serde(default)]
+    pub rounds_paid: u32,
+    /// Reward tier the contributor currently qualifies for.
+    pub tier: Option<u32>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+pub struct ContributionEntry {
+    pub amount: Uint128,
+    pub contributed_at: u64,
+    /// ROSCA round the payment was for.
+    pub round: Option<u32>,
+}
+
+/// Shared bill-splitting ledger. Balances are kept per member in `EXPENSE_BALANCES`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+pub struct ExpenseGroup {
+    pub id: u64,
+    pub creator: Addr,
+    pub members: Vec<Addr>,
+    pub denom: String,
+    pub created_at: u64,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const NEXT_POOL_ID: Item<u64> = Item::new("next_pool_id");
-pub const POOLS: Map<u64, Pool> = Map::new("pools");
+
+pub struct PoolIndexes<'a> {
+    pub creator: MultiIndex<'a, Addr, Pool, u64>,
+}
+
+impl<'a> IndexList<Pool> for PoolIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Pool>> + '_> {
+        let v: Vec<&dyn Index<Pool>> = vec![&self.creator];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn pools<'a>() -> IndexedMap<'a, u64, Pool, PoolIndexes<'a>> {
+    let indexes = PoolIndexes {
+        creator: MultiIndex::new(|_pk, p| p.creator.clone(), "pools", "pools__creator"),
+    };
+    IndexedMap::new("pools", indexes)
+}
+
 pub const CONTRIBUTIONS: Map<(u64, Addr), Contribution> = Map::new("contributions");
+/// Every deposit, keyed by (pool_id, contributor, index). Kept across refunds for audit.
+pub const CONTRIBUTION_ENTRIES: Map<(u64, Addr, u32), ContributionEntry> =
+    Map::new("contribution_entries");
+/// Pools each address has contributed to, keyed by (contributor, pool_id).
+pub const PARTICIPANT_POOLS: Map<(Addr, u64), Empty> = Map::new("participant_pools");
+/// Target pools with an expiry, keyed by (expiry_ts, pool_id), for `ProcessExpired`.
+pub const EXPIRY_QUEUE: Map<(u64, u64), Empty> = Map::new("expiry_queue");
+pub const NEXT_EXPENSE_GROUP_ID: Item<u64> = Item::new("next_expense_group_id");
+pub const EXPENSE_GROUPS: Map<u64, ExpenseGroup> = Map::new("expense_groups");
+/// Net balance per member: positive when owed money, negative when in debt.
+pub const EXPENSE_BALANCES: Map<(u64, Addr), Int128> = Map::new("expense_balances");
+/// Backers of each reward tier, keyed by (pool_id, tier, contributor).
+pub const TIER_BACKERS: Map<(u64, u32, Addr), Empty> = Map::new("tier_backers");
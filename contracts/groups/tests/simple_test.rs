@@ -1,8 +1,13 @@
-use cosmwasm_std::{Addr, Coin};
+use cosmwasm_std::{Addr, Coin, Int128, Timestamp, Uint128};
+use cw_multi_test::error::AnyResult;
 use cw_multi_test::{App, ContractWrapper, Executor};
 use seimoney_groups::{
     contract::{execute, instantiate, query},
-    msg::{ExecuteMsg, InstantiateMsg, QueryMsg, PoolResp, ContributionResp},
+    msg::{
+        ContributionDetailResp, ContributionEntryResp, ContributionResp, ExecuteMsg, ExpenseSplit,
+        InstantiateMsg, MemberBalanceResp, Participant, PoolResp, QueryMsg, RoscaScheduleResp,
+        SettlementResp,
+    },
 };
 
 const ADMIN: &str = "sei1admin";
@@ -27,6 +32,10 @@ fn mock_app() -> App {
 }
 
 fn setup_contract(app: &mut App) -> Addr {
+    setup_contract_with_alias(app, None)
+}
+
+fn setup_contract_with_alias(app: &mut App, alias_contract: Option<&Addr>) -> Addr {
     let code = ContractWrapper::new(execute, instantiate, query);
     let code_id = app.store_code(Box::new(code));
     
@@ -34,6 +43,7 @@ fn setup_contract(app: &mut App) -> Addr {
         admin: Some(ADMIN.to_string()),
         default_denom: "usei".to_string(),
         max_participants: Some(10),
+        alias_contract: alias_contract.map(|a| a.to_string()),
     };
     
     app.instantiate_contract(
@@ -66,6 +76,11 @@ fn test_create_pool() {
         max_participants: Some(10),
         memo: Some("Test pool".to_string()),
         expiry_ts: None,
+        allowlist: None,
+        beneficiaries: None,
+        min_contribution: None,
+        max_contribution_per_address: None,
+        hard_cap: None,
     };
     
     let result = app.execute_contract(
@@ -88,8 +103,8 @@ fn test_create_pool() {
     
     assert_eq!(result.creator, USER1);
     assert_eq!(result.target, Coin::new(1000, "usei"));
-    assert_eq!(result.distributed, false);
-    assert_eq!(result.cancelled, false);
+    assert!(!result.distributed);
+    assert!(!result.cancelled);
 }
 
 #[test]
@@ -103,6 +118,11 @@ fn test_contribute() {
         max_participants: Some(10),
         memo: Some("Test pool".to_string()),
         expiry_ts: None,
+        allowlist: None,
+        beneficiaries: None,
+        min_contribution: None,
+        max_contribution_per_address: None,
+        hard_cap: None,
     };
     
     app.execute_contract(
@@ -141,3 +161,1102 @@ fn test_contribute() {
     assert_eq!(result[0].contributor, USER2);
     assert_eq!(result[0].amount, "10");
 }
+
+fn create_pool(app: &mut App, contract_addr: &Addr, target: u128) {
+    let msg = ExecuteMsg::CreatePool {
+        target: Coin::new(target, "usei"),
+        max_participants: Some(10),
+        memo: None,
+        expiry_ts: None,
+        allowlist: None,
+        beneficiaries: None,
+        min_contribution: None,
+        max_contribution_per_address: None,
+        hard_cap: None,
+    };
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &[])
+        .unwrap();
+}
+
+fn balance(app: &App, addr: &str) -> u128 {
+    app.wrap().query_balance(addr, "usei").unwrap().amount.u128()
+}
+
+#[test]
+fn test_contribute_requires_funds() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+    create_pool(&mut app, &contract_addr, 1000);
+
+    let msg = ExecuteMsg::Contribute {
+        pool_id: 1,
+        amount: Coin::new(100, "usei"),
+    };
+
+    // No funds attached
+    let result = app.execute_contract(Addr::unchecked(USER2), contract_addr.clone(), &msg, &[]);
+    assert!(result.is_err());
+
+    // Attached funds differ from the declared amount
+    let result = app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &msg,
+        &[Coin::new(50, "usei")],
+    );
+    assert!(result.is_err());
+
+    assert_eq!(balance(&app, USER2), 10000);
+    assert_eq!(balance(&app, contract_addr.as_str()), 0);
+}
+
+#[test]
+fn test_overpayment_refunded_and_distribute_reconciles() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+    create_pool(&mut app, &contract_addr, 1000);
+
+    let msg = ExecuteMsg::Contribute {
+        pool_id: 1,
+        amount: Coin::new(1500, "usei"),
+    };
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &msg,
+        &[Coin::new(1500, "usei")],
+    )
+    .unwrap();
+
+    // Only the remaining 1000 is kept
+    assert_eq!(balance(&app, USER2), 9000);
+    assert_eq!(balance(&app, contract_addr.as_str()), 1000);
+
+    let pool: PoolResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetPool { id: 1 })
+        .unwrap();
+    assert_eq!(pool.current, "1000");
+
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::Distribute { pool_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(balance(&app, USER1), 11000);
+    assert_eq!(balance(&app, contract_addr.as_str()), 0);
+}
+
+#[test]
+fn test_refund_contribution_reconciles() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+    create_pool(&mut app, &contract_addr, 1000);
+
+    let msg = ExecuteMsg::Contribute {
+        pool_id: 1,
+        amount: Coin::new(300, "usei"),
+    };
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &msg,
+        &[Coin::new(300, "usei")],
+    )
+    .unwrap();
+    assert_eq!(balance(&app, contract_addr.as_str()), 300);
+
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::CancelPool { pool_id: 1 },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &ExecuteMsg::RefundContribution { pool_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(balance(&app, USER2), 10000);
+    assert_eq!(balance(&app, contract_addr.as_str()), 0);
+}
+
+#[test]
+fn test_contributions_accumulate() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+    create_pool(&mut app, &contract_addr, 1000);
+
+    for (ts, amount) in [(100, 200u128), (200, 300)] {
+        app.update_block(|block| block.time = Timestamp::from_seconds(ts));
+        let msg = ExecuteMsg::Contribute {
+            pool_id: 1,
+            amount: Coin::new(amount, "usei"),
+        };
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &msg,
+            &[Coin::new(amount, "usei")],
+        )
+        .unwrap();
+    }
+
+    let detail: ContributionDetailResp = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::GetContribution {
+                pool_id: 1,
+                address: USER2.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(detail.total, "500");
+    assert_eq!(detail.entry_count, 2);
+    assert_eq!(detail.history.len(), 2);
+    assert_eq!(detail.history[0].amount, "200");
+    assert_eq!(detail.history[1].contributed_at, 200);
+
+    let history: Vec<ContributionEntryResp> = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::ListContributionEntries {
+                pool_id: 1,
+                address: USER2.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].amount, "200");
+    assert_eq!(history[0].contributed_at, 100);
+    assert_eq!(history[1].amount, "300");
+    assert_eq!(history[1].contributed_at, 200);
+
+    // Pages continue after the given index
+    let page: Vec<ContributionEntryResp> = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::ListContributionEntries {
+                pool_id: 1,
+                address: USER2.to_string(),
+                start_after: Some(0),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].index, 1);
+
+    // Refund returns both deposits
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::CancelPool { pool_id: 1 },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &ExecuteMsg::RefundContribution { pool_id: 1 },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(balance(&app, USER2), 10000);
+}
+
+const USER3: &str = "sei1user3";
+
+fn setup_alias_contract(app: &mut App) -> Addr {
+    let code = ContractWrapper::new(
+        seimoney_alias::contract::execute,
+        seimoney_alias::contract::instantiate,
+        seimoney_alias::contract::query,
+    );
+    let code_id = app.store_code(Box::new(code));
+
+    let msg = seimoney_alias::msg::InstantiateMsg {
+        admin: Some(ADMIN.to_string()),
+        min_username_length: None,
+        max_username_length: None,
+    };
+    app.instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "SeiMoney Alias", None)
+        .unwrap()
+}
+
+fn contribute(app: &mut App, contract_addr: &Addr, sender: &str, amount: u128) -> AnyResult<()> {
+    let msg = ExecuteMsg::Contribute {
+        pool_id: 1,
+        amount: Coin::new(amount, "usei"),
+    };
+    app.execute_contract(
+        Addr::unchecked(sender),
+        contract_addr.clone(),
+        &msg,
+        &[Coin::new(amount, "usei")],
+    )
+    .map(|_| ())
+}
+
+#[test]
+fn test_pool_max_participants() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+
+    // Cannot exceed the contract-wide cap of 10
+    let msg = ExecuteMsg::CreatePool {
+        target: Coin::new(1000, "usei"),
+        max_participants: Some(11),
+        memo: None,
+        expiry_ts: None,
+        allowlist: None,
+        beneficiaries: None,
+        min_contribution: None,
+        max_contribution_per_address: None,
+        hard_cap: None,
+    };
+    let result = app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &[]);
+    assert!(result.is_err());
+
+    let msg = ExecuteMsg::CreatePool {
+        target: Coin::new(1000, "usei"),
+        max_participants: Some(1),
+        memo: None,
+        expiry_ts: None,
+        allowlist: None,
+        beneficiaries: None,
+        min_contribution: None,
+        max_contribution_per_address: None,
+        hard_cap: None,
+    };
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &[])
+        .unwrap();
+
+    contribute(&mut app, &contract_addr, USER2, 100).unwrap();
+    // The same contributor may top up, a new one may not join
+    contribute(&mut app, &contract_addr, USER2, 100).unwrap();
+    assert!(contribute(&mut app, &contract_addr, USER1, 100).is_err());
+
+    let pool: PoolResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr, &QueryMsg::GetPool { id: 1 })
+        .unwrap();
+    assert_eq!(pool.max_participants, 1);
+    assert_eq!(pool.participants, vec![USER2.to_string()]);
+}
+
+#[test]
+fn test_pool_allowlist() {
+    let mut app = mock_app();
+    let alias_addr = setup_alias_contract(&mut app);
+    let contract_addr = setup_contract_with_alias(&mut app, Some(&alias_addr));
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(cw_multi_test::BankSudo::Mint {
+        to_address: USER3.to_string(),
+        amount: vec![Coin::new(10000, "usei")],
+    }))
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        alias_addr,
+        &seimoney_alias::msg::ExecuteMsg::Register {
+            username: "carol".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::CreatePool {
+        target: Coin::new(1000, "usei"),
+        max_participants: None,
+        memo: None,
+        expiry_ts: None,
+        allowlist: Some(vec![Participant::Address(USER2.to_string())]),
+        beneficiaries: None,
+        min_contribution: None,
+        max_contribution_per_address: None,
+        hard_cap: None,
+    };
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &[])
+        .unwrap();
+
+    contribute(&mut app, &contract_addr, USER2, 100).unwrap();
+    assert!(contribute(&mut app, &contract_addr, USER3, 100).is_err());
+
+    // Only the creator manages the allowlist
+    let add = ExecuteMsg::AddParticipants {
+        pool_id: 1,
+        participants: vec![Participant::Alias("carol".to_string())],
+    };
+    let result = app.execute_contract(Addr::unchecked(USER2), contract_addr.clone(), &add, &[]);
+    assert!(result.is_err());
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &add, &[])
+        .unwrap();
+    contribute(&mut app, &contract_addr, USER3, 100).unwrap();
+
+    let remove = ExecuteMsg::RemoveParticipants {
+        pool_id: 1,
+        participants: vec![Participant::Address(USER2.to_string())],
+    };
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &remove, &[])
+        .unwrap();
+    assert!(contribute(&mut app, &contract_addr, USER2, 100).is_err());
+
+    let pool: PoolResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr, &QueryMsg::GetPool { id: 1 })
+        .unwrap();
+    assert_eq!(pool.allowlist, Some(vec![USER3.to_string()]));
+    assert_eq!(pool.current, "200");
+}
+
+#[test]
+fn test_distribute_to_beneficiaries() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+
+    // Weights must add up to 100%
+    let mut msg = ExecuteMsg::CreatePool {
+        target: Coin::new(1001, "usei"),
+        max_participants: None,
+        memo: None,
+        expiry_ts: None,
+        allowlist: None,
+        beneficiaries: Some(vec![(USER2.to_string(), 5000), (USER3.to_string(), 4000)]),
+        min_contribution: None,
+        max_contribution_per_address: None,
+        hard_cap: None,
+    };
+    let result = app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &[]);
+    assert!(result.is_err());
+
+    if let ExecuteMsg::CreatePool { beneficiaries, .. } = &mut msg {
+        *beneficiaries = Some(vec![(USER3.to_string(), 3333), (ADMIN.to_string(), 6667)]);
+    }
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &[])
+        .unwrap();
+
+    contribute(&mut app, &contract_addr, USER2, 1001).unwrap();
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::Distribute { pool_id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    // 1001 * 33.33% = 333.63 and 1001 * 66.67% = 667.37, the dust goes to the first
+    assert_eq!(balance(&app, USER3), 334);
+    assert_eq!(balance(&app, ADMIN), 667);
+    assert_eq!(balance(&app, USER1), 10000);
+    assert_eq!(balance(&app, contract_addr.as_str()), 0);
+
+    let pool: PoolResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr, &QueryMsg::GetPool { id: 1 })
+        .unwrap();
+    assert_eq!(pool.beneficiaries[1], (ADMIN.to_string(), 6667));
+}
+
+#[test]
+fn test_expense_group_settle_up() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(cw_multi_test::BankSudo::Mint {
+        to_address: USER3.to_string(),
+        amount: vec![Coin::new(10000, "usei")],
+    }))
+    .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::CreateExpenseGroup {
+            members: vec![USER2.to_string(), USER3.to_string()],
+        },
+        &[],
+    )
+    .unwrap();
+
+    let record = |payer: &str, amount: u128, split: ExpenseSplit| ExecuteMsg::RecordExpense {
+        group_id: 1,
+        payer: payer.to_string(),
+        amount: Uint128::new(amount),
+        split,
+        memo: None,
+    };
+
+    // USER1 pays 300 for everyone, USER2 pays 90 on USER3's behalf
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &record(USER1, 300, ExpenseSplit::Equal {}),
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &record(USER2, 90, ExpenseSplit::Exact(vec![(USER3.to_string(), Uint128::new(90))])),
+        &[],
+    )
+    .unwrap();
+
+    // Members cannot record expenses paid by someone else
+    let result = app.execute_contract(
+        Addr::unchecked(USER3),
+        contract_addr.clone(),
+        &record(USER1, 1000, ExpenseSplit::Equal {}),
+        &[],
+    );
+    assert!(result.is_err());
+
+    // Percentages must add up to 100% and name members only
+    let bad = [
+        ExpenseSplit::Percent(vec![(USER2.to_string(), 5000)]),
+        ExpenseSplit::Percent(vec![(USER2.to_string(), 5000), (ADMIN.to_string(), 5000)]),
+        ExpenseSplit::Exact(vec![(USER2.to_string(), Uint128::new(10))]),
+    ];
+    for split in bad {
+        let result = app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &record(USER1, 100, split),
+            &[],
+        );
+        assert!(result.is_err());
+    }
+
+    let balances: Vec<MemberBalanceResp> = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::ExpenseBalances { group_id: 1 })
+        .unwrap();
+    let nets: Vec<i128> = balances.iter().map(|b| b.net.i128()).collect();
+    assert_eq!(nets, vec![200, -10, -190]);
+
+    let plan: Vec<SettlementResp> = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::SettlementPlan { group_id: 1 })
+        .unwrap();
+    assert_eq!(plan.len(), 2);
+    assert_eq!(plan[0].from, USER3);
+    assert_eq!(plan[0].to, USER1);
+    assert_eq!(plan[0].amount, Uint128::new(190));
+
+    // Settling needs exactly the debtor's share of the plan
+    let settle = ExecuteMsg::SettleUp { group_id: 1 };
+    let result = app.execute_contract(
+        Addr::unchecked(USER3),
+        contract_addr.clone(),
+        &settle,
+        &[Coin::new(100, "usei")],
+    );
+    assert!(result.is_err());
+    app.execute_contract(
+        Addr::unchecked(USER3),
+        contract_addr.clone(),
+        &settle,
+        &[Coin::new(190, "usei")],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &settle,
+        &[Coin::new(10, "usei")],
+    )
+    .unwrap();
+
+    assert_eq!(balance(&app, USER1), 10200);
+    assert_eq!(balance(&app, USER2), 9990);
+    assert_eq!(balance(&app, USER3), 9810);
+
+    let balances: Vec<MemberBalanceResp> = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::ExpenseBalances { group_id: 1 })
+        .unwrap();
+    assert!(balances.iter().all(|b| b.net == Int128::zero()));
+
+    // Nothing left to settle
+    let result = app.execute_contract(Addr::unchecked(USER1), contract_addr, &settle, &[]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_rosca_rounds() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(cw_multi_test::BankSudo::Mint {
+        to_address: USER3.to_string(),
+        amount: vec![Coin::new(10000, "usei")],
+    }))
+    .unwrap();
+    app.update_block(|block| block.time = Timestamp::from_seconds(1000));
+
+    let msg = ExecuteMsg::CreateRosca {
+        members: vec![USER1.to_string(), USER2.to_string(), USER3.to_string()],
+        contribution: Coin::new(100, "usei"),
+        period_secs: 100,
+        start_ts: 1000,
+        randomize_order: false,
+        late_penalty_bps: 500,
+        memo: None,
+    };
+    app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &msg, &[])
+        .unwrap();
+
+    let distribute = ExecuteMsg::Distribute { pool_id: 1 };
+
+    // Round 0: everyone pays on time, USER1 takes the pot
+    contribute(&mut app, &contract_addr, USER1, 100).unwrap();
+    contribute(&mut app, &contract_addr, USER2, 100).unwrap();
+    let result = app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &distribute, &[]);
+    assert!(result.is_err());
+    contribute(&mut app, &contract_addr, USER3, 100).unwrap();
+    app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &distribute, &[])
+        .unwrap();
+    assert_eq!(balance(&app, USER1), 10200);
+
+    // Round 1 has not opened yet
+    assert!(contribute(&mut app, &contract_addr, USER1, 100).is_err());
+
+    app.update_block(|block| block.time = Timestamp::from_seconds(1150));
+    contribute(&mut app, &contract_addr, USER1, 100).unwrap();
+    contribute(&mut app, &contract_addr, USER2, 100).unwrap();
+
+    // USER3 is late and owes the 5% penalty on top
+    app.update_block(|block| block.time = Timestamp::from_seconds(1250));
+    assert!(contribute(&mut app, &contract_addr, USER3, 100).is_err());
+    contribute(&mut app, &contract_addr, USER3, 105).unwrap();
+    app.execute_contract(Addr::unchecked(USER3), contract_addr.clone(), &distribute, &[])
+        .unwrap();
+    assert_eq!(balance(&app, USER2), 9800 + 305);
+
+    let schedule: RoscaScheduleResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::RoscaSchedule { pool_id: 1 })
+        .unwrap();
+    assert_eq!(schedule.current_round, 2);
+    assert_eq!(schedule.rounds[1].recipient, USER2);
+    assert_eq!(schedule.rounds[1].due_ts, 1200);
+    assert!(schedule.rounds[1].paid_out);
+    assert_eq!(schedule.rounds[1].paid_members.len(), 3);
+    assert_eq!(schedule.rounds[2].recipient, USER3);
+    assert!(schedule.rounds[2].paid_members.is_empty());
+
+    // Outsiders cannot join and the pool cannot be cancelled
+    assert!(contribute(&mut app, &contract_addr, ADMIN, 100).is_err());
+    let result = app.execute_contract(
+        Addr::unchecked(ADMIN),
+        contract_addr.clone(),
+        &ExecuteMsg::CancelPool { pool_id: 1 },
+        &[],
+    );
+    assert!(result.is_err());
+
+    for member in [USER1, USER2, USER3] {
+        contribute(&mut app, &contract_addr, member, 100).unwrap();
+    }
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &distribute, &[])
+        .unwrap();
+
+    let pool: PoolResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetPool { id: 1 })
+        .unwrap();
+    assert!(pool.distributed);
+    assert_eq!(pool.kind, "rosca");
+    assert_eq!(balance(&app, contract_addr.as_str()), 0);
+}
+
+#[test]
+fn test_rosca_default_refunds_round() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+    app.update_block(|block| block.time = Timestamp::from_seconds(1000));
+
+    let msg = ExecuteMsg::CreateRosca {
+        members: vec![USER1.to_string(), USER2.to_string(), USER3.to_string()],
+        contribution: Coin::new(100, "usei"),
+        period_secs: 100,
+        start_ts: 1000,
+        randomize_order: false,
+        late_penalty_bps: 500,
+        memo: None,
+    };
+    app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &msg, &[])
+        .unwrap();
+
+    // USER3 never pays round 0
+    contribute(&mut app, &contract_addr, USER1, 100).unwrap();
+    contribute(&mut app, &contract_addr, USER2, 100).unwrap();
+
+    // Cancelling is only allowed a full period after the round fell due
+    let cancel = ExecuteMsg::CancelPool { pool_id: 1 };
+    app.update_block(|block| block.time = Timestamp::from_seconds(1150));
+    // Meanwhile USER1 already pays into round 1
+    contribute(&mut app, &contract_addr, USER1, 100).unwrap();
+    let result = app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &cancel, &[]);
+    assert!(result.is_err());
+
+    // Members cannot pull their whole history out one by one
+    let refund = ExecuteMsg::RefundContribution { pool_id: 1 };
+    let result = app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &refund, &[]);
+    assert!(result.is_err());
+
+    // Anyone may wind up the defaulted club, and every unpaid-out round is refunded
+    app.update_block(|block| block.time = Timestamp::from_seconds(1200));
+    app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &cancel, &[])
+        .unwrap();
+    assert_eq!(balance(&app, USER1), 10000);
+    assert_eq!(balance(&app, USER2), 10000);
+    assert_eq!(balance(&app, contract_addr.as_str()), 0);
+
+    let pool: PoolResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetPool { id: 1 })
+        .unwrap();
+    assert!(pool.cancelled);
+    assert!(contribute(&mut app, &contract_addr, USER1, 100).is_err());
+    let result = app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &cancel, &[]);
+    assert!(result.is_err());
+}
+
+fn create_expiring_pool(app: &mut App, contract_addr: &Addr, expiry_ts: u64) {
+    let msg = ExecuteMsg::CreatePool {
+        target: Coin::new(1000, "usei"),
+        max_participants: None,
+        memo: None,
+        expiry_ts: Some(expiry_ts),
+        allowlist: None,
+        beneficiaries: None,
+        min_contribution: None,
+        max_contribution_per_address: None,
+        hard_cap: None,
+    };
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &[])
+        .unwrap();
+}
+
+#[test]
+fn test_refund_after_failed_expiry() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+    app.update_block(|block| block.time = Timestamp::from_seconds(1000));
+    create_expiring_pool(&mut app, &contract_addr, 2000);
+
+    contribute(&mut app, &contract_addr, USER2, 300).unwrap();
+
+    let refund = ExecuteMsg::RefundContribution { pool_id: 1 };
+    let result = app.execute_contract(Addr::unchecked(USER2), contract_addr.clone(), &refund, &[]);
+    assert!(result.is_err());
+
+    // Expired under target: no need to wait for the creator to cancel
+    app.update_block(|block| block.time = Timestamp::from_seconds(2000));
+    app.execute_contract(Addr::unchecked(USER2), contract_addr.clone(), &refund, &[])
+        .unwrap();
+    assert_eq!(balance(&app, USER2), 10000);
+
+    // The refund leaves the pool's totals and participant index
+    let pool: PoolResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetPool { id: 1 })
+        .unwrap();
+    assert_eq!(pool.current, "0");
+    assert!(pool.participants.is_empty());
+    let pools: Vec<PoolResp> = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::ListPoolsByParticipant {
+                address: USER2.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert!(pools.is_empty());
+
+    // Only once
+    let result = app.execute_contract(Addr::unchecked(USER2), contract_addr, &refund, &[]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_process_expired() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+    app.update_block(|block| block.time = Timestamp::from_seconds(1000));
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(cw_multi_test::BankSudo::Mint {
+        to_address: USER3.to_string(),
+        amount: vec![Coin::new(10000, "usei")],
+    }))
+    .unwrap();
+
+    create_expiring_pool(&mut app, &contract_addr, 2000);
+    create_expiring_pool(&mut app, &contract_addr, 3000);
+    for user in [USER1, USER2, USER3] {
+        contribute(&mut app, &contract_addr, user, 100).unwrap();
+    }
+    let msg = ExecuteMsg::Contribute {
+        pool_id: 2,
+        amount: Coin::new(200, "usei"),
+    };
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &msg,
+        &[Coin::new(200, "usei")],
+    )
+    .unwrap();
+
+    let crank = |limit| ExecuteMsg::ProcessExpired { limit: Some(limit) };
+
+    // Nothing has expired yet
+    app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &crank(10), &[])
+        .unwrap();
+    assert_eq!(balance(&app, contract_addr.as_str()), 500);
+
+    // One pool plus two contributors per call
+    app.update_block(|block| block.time = Timestamp::from_seconds(2500));
+    app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &crank(3), &[])
+        .unwrap();
+    assert_eq!(balance(&app, contract_addr.as_str()), 300);
+    app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &crank(3), &[])
+        .unwrap();
+    assert_eq!(balance(&app, contract_addr.as_str()), 200);
+    assert_eq!(balance(&app, USER1), 10000);
+    assert_eq!(balance(&app, USER3), 10000);
+
+    let pool: PoolResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetPool { id: 1 })
+        .unwrap();
+    assert!(pool.cancelled);
+    assert_eq!(pool.current, "0");
+    assert!(pool.participants.is_empty());
+
+    // Pool 2 is still running
+    let pool: PoolResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetPool { id: 2 })
+        .unwrap();
+    assert!(!pool.cancelled);
+
+    app.update_block(|block| block.time = Timestamp::from_seconds(3000));
+    app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &crank(10), &[])
+        .unwrap();
+    assert_eq!(balance(&app, USER2), 10000);
+    assert_eq!(balance(&app, contract_addr.as_str()), 0);
+}
+
+#[test]
+fn test_contribution_limits_and_stretch_goal() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+    app.update_block(|block| block.time = Timestamp::from_seconds(1000));
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(cw_multi_test::BankSudo::Mint {
+        to_address: USER3.to_string(),
+        amount: vec![Coin::new(10000, "usei")],
+    }))
+    .unwrap();
+
+    let create = |expiry_ts: Option<u64>| ExecuteMsg::CreatePool {
+        target: Coin::new(1000, "usei"),
+        max_participants: None,
+        memo: None,
+        expiry_ts,
+        allowlist: None,
+        beneficiaries: None,
+        min_contribution: Some(Uint128::new(100)),
+        max_contribution_per_address: Some(Uint128::new(800)),
+        hard_cap: Some(Uint128::new(1500)),
+    };
+
+    // A stretch goal needs an end date
+    let result = app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &create(None), &[]);
+    assert!(result.is_err());
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &create(Some(2000)),
+        &[],
+    )
+    .unwrap();
+
+    assert!(contribute(&mut app, &contract_addr, USER2, 50).is_err());
+    assert!(contribute(&mut app, &contract_addr, USER2, 900).is_err());
+    contribute(&mut app, &contract_addr, USER2, 800).unwrap();
+    assert!(contribute(&mut app, &contract_addr, USER2, 100).is_err());
+    contribute(&mut app, &contract_addr, USER3, 500).unwrap();
+
+    // Past the soft target but still running
+    let distribute = ExecuteMsg::Distribute { pool_id: 1 };
+    let result = app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &distribute, &[]);
+    assert!(result.is_err());
+
+    // Only 200 fits under the hard cap, the rest is returned
+    contribute(&mut app, &contract_addr, USER1, 400).unwrap();
+    assert_eq!(balance(&app, USER1), 9800);
+    assert!(contribute(&mut app, &contract_addr, USER3, 100).is_err());
+
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &distribute, &[])
+        .unwrap();
+    assert_eq!(balance(&app, USER1), 11300);
+
+    // A second campaign only meets its soft target and closes at expiry
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &create(Some(3000)),
+        &[],
+    )
+    .unwrap();
+    for (user, amount) in [(USER2, 600u128), (USER3, 500)] {
+        let msg = ExecuteMsg::Contribute {
+            pool_id: 2,
+            amount: Coin::new(amount, "usei"),
+        };
+        app.execute_contract(
+            Addr::unchecked(user),
+            contract_addr.clone(),
+            &msg,
+            &[Coin::new(amount, "usei")],
+        )
+        .unwrap();
+    }
+    let distribute = ExecuteMsg::Distribute { pool_id: 2 };
+    let result = app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &distribute, &[]);
+    assert!(result.is_err());
+    app.update_block(|block| block.time = Timestamp::from_seconds(3000));
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &distribute, &[])
+        .unwrap();
+    assert_eq!(balance(&app, USER1), 12400);
+}
+
+#[test]
+fn test_reward_tiers() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+    create_pool(&mut app, &contract_addr, 5000);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(cw_multi_test::BankSudo::Mint {
+        to_address: USER3.to_string(),
+        amount: vec![Coin::new(10000, "usei")],
+    }))
+    .unwrap();
+
+    let tier = |min_amount: u128, description: &str, limit: Option<u32>| ExecuteMsg::AddRewardTier {
+        pool_id: 1,
+        min_amount: Uint128::new(min_amount),
+        description: description.to_string(),
+        limit,
+    };
+    let result = app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &tier(100, "Sticker", None),
+        &[],
+    );
+    assert!(result.is_err());
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &tier(100, "Sticker", None),
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &tier(500, "T-shirt", Some(1)),
+        &[],
+    )
+    .unwrap();
+
+    // Topping up moves USER2 into the higher tier
+    contribute(&mut app, &contract_addr, USER2, 100).unwrap();
+    let res = app
+        .execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::Contribute {
+                pool_id: 1,
+                amount: Coin::new(400, "usei"),
+            },
+            &[Coin::new(400, "usei")],
+        )
+        .unwrap();
+    let claim = res
+        .events
+        .iter()
+        .find(|e| e.ty == "wasm-seimoney.claim_tier")
+        .unwrap();
+    assert!(claim
+        .attributes
+        .iter()
+        .any(|a| a.key == "previous_tier" && a.value == "0"));
+
+    // The T-shirt tier is sold out, so USER3 falls back to the sticker
+    contribute(&mut app, &contract_addr, USER3, 600).unwrap();
+
+    let backers = |app: &App, tier: u32| -> Vec<ContributionResp> {
+        app.wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::ListTierBackers {
+                    pool_id: 1,
+                    tier,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap()
+    };
+    let top = backers(&app, 1);
+    assert_eq!(top.len(), 1);
+    assert_eq!(top[0].contributor, USER2);
+    assert_eq!(top[0].amount, "500");
+    let bottom = backers(&app, 0);
+    assert_eq!(bottom.len(), 1);
+    assert_eq!(bottom[0].contributor, USER3);
+
+    let pool: PoolResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetPool { id: 1 })
+        .unwrap();
+    assert_eq!(pool.tiers[0].backers, 1);
+    assert_eq!(pool.tiers[1].backers, 1);
+    assert_eq!(pool.tiers[1].description, "T-shirt");
+
+    // Refunds give the tier back
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::CancelPool { pool_id: 1 },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &ExecuteMsg::RefundContribution { pool_id: 1 },
+        &[],
+    )
+    .unwrap();
+    assert!(backers(&app, 1).is_empty());
+}
+
+#[test]
+fn test_pool_scoped_queries() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(cw_multi_test::BankSudo::Mint {
+        to_address: USER3.to_string(),
+        amount: vec![Coin::new(10000, "usei")],
+    }))
+    .unwrap();
+
+    create_pool(&mut app, &contract_addr, 1000);
+    create_pool(&mut app, &contract_addr, 1000);
+    let msg = ExecuteMsg::CreatePool {
+        target: Coin::new(1000, "usei"),
+        max_participants: None,
+        memo: None,
+        expiry_ts: None,
+        allowlist: None,
+        beneficiaries: None,
+        min_contribution: None,
+        max_contribution_per_address: None,
+        hard_cap: None,
+    };
+    app.execute_contract(Addr::unchecked(USER2), contract_addr.clone(), &msg, &[])
+        .unwrap();
+
+    for (pool_id, user) in [(1, USER3), (2, USER2), (2, USER3)] {
+        let msg = ExecuteMsg::Contribute {
+            pool_id,
+            amount: Coin::new(100, "usei"),
+        };
+        app.execute_contract(
+            Addr::unchecked(user),
+            contract_addr.clone(),
+            &msg,
+            &[Coin::new(100, "usei")],
+        )
+        .unwrap();
+    }
+
+    let list = |app: &App, pool_id: u64, start_after: Option<&str>| -> Vec<String> {
+        let res: Vec<ContributionResp> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::ListContributions {
+                    pool_id,
+                    start_after: start_after.map(|a| a.to_string()),
+                    limit: None,
+                },
+            )
+            .unwrap();
+        res.into_iter().map(|c| c.contributor).collect()
+    };
+    assert_eq!(list(&app, 1, None), vec![USER3.to_string()]);
+    assert_eq!(list(&app, 2, None), vec![USER2.to_string(), USER3.to_string()]);
+    assert_eq!(list(&app, 2, Some(USER2)), vec![USER3.to_string()]);
+    assert!(list(&app, 3, None).is_empty());
+
+    let ids = |msg: QueryMsg| -> Vec<u64> {
+        let res: Vec<PoolResp> = app.wrap().query_wasm_smart(contract_addr.clone(), &msg).unwrap();
+        res.into_iter().map(|p| p.id).collect()
+    };
+    assert_eq!(
+        ids(QueryMsg::ListPoolsByCreator {
+            creator: USER1.to_string(),
+            start_after: None,
+            limit: None,
+        }),
+        vec![1, 2]
+    );
+    assert_eq!(
+        ids(QueryMsg::ListPoolsByCreator {
+            creator: USER1.to_string(),
+            start_after: Some(1),
+            limit: None,
+        }),
+        vec![2]
+    );
+    assert_eq!(
+        ids(QueryMsg::ListPoolsByParticipant {
+            address: USER3.to_string(),
+            start_after: None,
+            limit: None,
+        }),
+        vec![1, 2]
+    );
+    assert_eq!(
+        ids(QueryMsg::ListPoolsByParticipant {
+            address: USER2.to_string(),
+            start_after: None,
+            limit: None,
+        }),
+        vec![2]
+    );
+}
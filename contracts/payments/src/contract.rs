@@ -1,12 +1,18 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
+use std::collections::BTreeMap;
+
 use cosmwasm_std::{
-    ensure, to_json_binary, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult, Timestamp, Uint128, Order,
+    ensure, from_json, to_json_binary, to_json_vec, Addr, BankMsg, Binary, Coin, Coins, CosmosMsg, Deps, DepsMut,
+    Empty, Env, Event, MessageInfo, Response, StdError, StdResult, Storage, Timestamp, Uint128, Order, WasmMsg,
 };
 use cw2::set_contract_version;
+use cw20::{Cw20Coin, Cw20ExecuteMsg, Cw20ReceiveMsg};
 use cw_storage_plus::Bound;
+use seimoney_common::math::calculate_fee;
+use seimoney_common::validation::validate_fee_bps;
+use sha2::{Digest, Sha256};
 
 use crate::error::ContractError::*;
 use crate::error::ContractError;
@@ -17,78 +23,259 @@ use crate::state::*;
 const CONTRACT_NAME: &str = "crates.io:seimoney-payments";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-#[entry_point]
-pub fn instantiate(deps: DepsMut, _env: Env, info: MessageInfo, msg: InstantiateMsg) -> StdResult<Response> {
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(deps: DepsMut, _env: Env, info: MessageInfo, msg: InstantiateMsg) -> Result<Response, ContractError> {
     let admin = match msg.admin {
         Some(a) => deps.api.addr_validate(&a)?,
         None => info.sender.clone(),
     };
-    let cfg = Config { admin, default_denom: msg.default_denom };
+    let fee_bps = msg.fee_bps.unwrap_or(0);
+    validate_fee_bps(fee_bps).map_err(|_| InvalidFee)?;
+    let alias_contract = msg.alias_contract.map(|a| deps.api.addr_validate(&a)).transpose()?;
+    let cfg = Config { admin, default_denom: msg.default_denom, fee_bps, alias_contract };
     CONFIG.save(deps.storage, &cfg)?;
     NEXT_ID.save(deps.storage, &1u64)?;
+    NEXT_STREAM_ID.save(deps.storage, &1u64)?;
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     Ok(Response::new().add_attribute("action", "instantiate"))
 }
 
-#[entry_point]
+#[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::CreateTransfer { recipient, amount, remark, expiry_ts } => exec_create(deps, env, info, recipient, amount, remark, expiry_ts),
-        ExecuteMsg::ClaimTransfer { id } => exec_claim(deps, env, info, id),
+        ExecuteMsg::CreateTransfer { recipient, amount, remark, expiry_ts, hashlock, arbiter, cancel_window_secs } => {
+            let item = TransferItem { recipient, amount, remark, expiry_ts, hashlock, arbiter, cancel_window_secs };
+            exec_create(deps, env, info, item)
+        }
+        ExecuteMsg::Receive(wrapper) => exec_receive(deps, env, info, wrapper),
+        ExecuteMsg::ClaimTransfer { id, preimage, to } => exec_claim(deps, env, info, id, preimage, to),
         ExecuteMsg::RefundTransfer { id } => exec_refund(deps, env, info, id),
+        ExecuteMsg::CancelTransfer { id } => exec_cancel(deps, env, info, id),
+        ExecuteMsg::ApproveTransfer { id } => exec_approve(deps, env, info, id),
+        ExecuteMsg::RejectTransfer { id } => exec_reject(deps, info, id),
+        ExecuteMsg::BatchCreateTransfer { items } => exec_batch_create(deps, env, info, items),
+        ExecuteMsg::BatchClaim { ids } => exec_batch_claim(deps, env, info, ids),
+        ExecuteMsg::BatchRefund { ids } => exec_batch_refund(deps, env, info, ids),
+        ExecuteMsg::UpdateConfig { fee_bps, alias_contract } => exec_update_config(deps, info, fee_bps, alias_contract),
+        ExecuteMsg::WithdrawFees { to } => exec_withdraw_fees(deps, info, to),
+        ExecuteMsg::CreateStream { recipient, rate_per_second, start, end } => exec_create_stream(deps, env, info, recipient, rate_per_second, start, end),
+        ExecuteMsg::WithdrawStream { id } => exec_withdraw_stream(deps, env, info, id),
+        ExecuteMsg::CancelStream { id } => exec_cancel_stream(deps, env, info, id),
+        ExecuteMsg::DepositVoucherFunds {} => exec_deposit_voucher_funds(deps, info),
+        ExecuteMsg::SetVoucherKey { pubkey } => exec_set_voucher_key(deps, info, pubkey),
+        ExecuteMsg::WithdrawVoucherFunds { amount } => exec_withdraw_voucher_funds(deps, info, amount),
+        ExecuteMsg::RedeemVoucher { voucher, signature } => exec_redeem_voucher(deps, env, voucher, signature),
     }
 }
 
-fn must_one_fund(info: &MessageInfo, denom: &str) -> Result<Coin, ContractError> {
-    let coin = info.funds.iter().find(|c| c.denom == denom).cloned().ok_or(InvalidFunds)?;
-    // Prevent sending more than one Coin to the same function (simplification)
-    ensure!(info.funds.len() == 1, InvalidFunds);
-    ensure!(coin.amount > Uint128::zero(), InvalidFunds);
-    Ok(coin)
+/// Checks that `amount` describes exactly the coins attached to the message and
+/// returns it sorted by denom.
+fn must_match_funds(info: &MessageInfo, amount: &[Coin]) -> Result<Vec<Coin>, ContractError> {
+    let expected = normalize_coins(amount)?;
+    ensure!(expected == normalize_coins(&info.funds)?, InvalidFunds);
+    Ok(expected)
 }
 
-fn exec_create(
-    deps: DepsMut,
+fn normalize_coins(coins: &[Coin]) -> Result<Vec<Coin>, ContractError> {
+    ensure!(!coins.is_empty(), InvalidFunds);
+    let mut coins = coins.to_vec();
+    coins.sort_by(|a, b| a.denom.cmp(&b.denom));
+    ensure!(coins.iter().all(|c| c.amount > Uint128::zero()), InvalidFunds);
+    ensure!(coins.windows(2).all(|w| w[0].denom != w[1].denom), DuplicateDenom);
+    Ok(coins)
+}
+
+fn exec_create(deps: DepsMut, env: Env, info: MessageInfo, item: TransferItem) -> Result<Response, ContractError> {
+    // Validate that the amount matches the funds sent
+    let amount = must_match_funds(&info, &item.amount)?;
+    let (recipient, _, terms) = split_item(item);
+
+    let tr = create_transfer(deps, &env, &info.sender, recipient, Asset::Native(amount), terms)?;
+
+    Ok(Response::new()
+        .add_event(evt_create(tr.id, tr.sender.as_str(), tr.recipient.as_str(), tr.recipient_alias.as_deref(), &tr.asset))
+        .add_attribute("action", "create_transfer"))
+}
+
+fn exec_receive(deps: DepsMut, env: Env, info: MessageInfo, wrapper: Cw20ReceiveMsg) -> Result<Response, ContractError> {
+    // The calling contract is the token being escrowed
+    ensure!(info.funds.is_empty(), InvalidFunds);
+    ensure!(!wrapper.amount.is_zero(), InvalidFunds);
+    let sender = deps.api.addr_validate(&wrapper.sender)?;
+    let asset = Asset::Cw20 { contract: info.sender, amount: wrapper.amount };
+
+    match from_json(&wrapper.msg)? {
+        ReceiveMsg::CreateTransfer { recipient, remark, expiry_ts, hashlock, arbiter, cancel_window_secs } => {
+            let terms = TransferTerms { remark, expiry_ts, hashlock, arbiter, cancel_window_secs };
+            let tr = create_transfer(deps, &env, &sender, recipient, asset, terms)?;
+
+            Ok(Response::new()
+                .add_event(evt_create(tr.id, tr.sender.as_str(), tr.recipient.as_str(), tr.recipient_alias.as_deref(), &tr.asset))
+                .add_attribute("action", "create_transfer"))
+        }
+    }
+}
+
+fn exec_batch_create(
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    recipient: String,
-    amount: Coin,
+    items: Vec<TransferItem>,
+) -> Result<Response, ContractError> {
+    ensure!(!items.is_empty(), EmptyBatch);
+
+    // The attached funds must cover exactly the sum of all items
+    let mut total = Coins::default();
+    for coin in items.iter().flat_map(|item| item.amount.iter()) {
+        total.add(coin.clone())?;
+    }
+    must_match_funds(&info, &total.into_vec())?;
+
+    let mut res = Response::new();
+    for (index, item) in items.into_iter().enumerate() {
+        let (recipient, amount, terms) = split_item(item);
+        let tr = normalize_coins(&amount)
+            .and_then(|amount| create_transfer(deps.branch(), &env, &info.sender, recipient, Asset::Native(amount), terms))
+            .map_err(|e| BatchItemFailed { index, reason: e.to_string() })?;
+        res = res.add_event(evt_create(tr.id, tr.sender.as_str(), tr.recipient.as_str(), tr.recipient_alias.as_deref(), &tr.asset));
+    }
+
+    Ok(res.add_attribute("action", "batch_create_transfer"))
+}
+
+/// Release conditions shared by every way of creating a transfer.
+struct TransferTerms {
     remark: Option<String>,
     expiry_ts: Option<u64>,
-) -> Result<Response, ContractError> {
-    let _cfg = CONFIG.load(deps.storage)?;
-    let rcpt = deps.api.addr_validate(&recipient)?;
-    
-    // Validate that the amount matches the funds sent
-    let sent_funds = must_one_fund(&info, &amount.denom)?;
-    ensure!(sent_funds.amount == amount.amount, InvalidFunds);
-    ensure!(sent_funds.denom == amount.denom, InvalidFunds);
+    hashlock: Option<Binary>,
+    arbiter: Option<String>,
+    cancel_window_secs: Option<u64>,
+}
 
-    if let Some(ts) = expiry_ts {
+fn split_item(item: TransferItem) -> (Recipient, Vec<Coin>, TransferTerms) {
+    let terms = TransferTerms {
+        remark: item.remark,
+        expiry_ts: item.expiry_ts,
+        hashlock: item.hashlock,
+        arbiter: item.arbiter,
+        cancel_window_secs: item.cancel_window_secs,
+    };
+    (item.recipient, item.amount, terms)
+}
+
+/// Stores a new open transfer of `asset`, which the caller has already received.
+fn create_transfer(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    recipient: Recipient,
+    asset: Asset,
+    terms: TransferTerms,
+) -> Result<Transfer, ContractError> {
+    let (rcpt, recipient_alias) = resolve_recipient(deps.as_ref(), recipient)?;
+
+    if let Some(ts) = terms.expiry_ts {
         ensure!(Timestamp::from_seconds(ts) > env.block.time, Expired);
     }
+    if let Some(h) = &terms.hashlock {
+        ensure!(h.len() == 32, InvalidHashlock);
+    }
+    let arbiter = terms.arbiter.map(|a| deps.api.addr_validate(&a)).transpose()?;
+    if let Some(arbiter) = &arbiter {
+        ensure!(terms.hashlock.is_none(), ConflictingReleaseConditions);
+        ensure!(arbiter != sender && *arbiter != rcpt, InvalidArbiter);
+    }
+    let cancel_until = match terms.cancel_window_secs {
+        Some(window) => {
+            ensure!(window > 0, InvalidCancelWindow);
+            let until = env.block.time.seconds() + window;
+            // The recipient must get a chance to claim after the window closes
+            if let Some(ts) = terms.expiry_ts {
+                ensure!(until < ts, InvalidCancelWindow);
+            }
+            Some(until)
+        }
+        None => None,
+    };
 
-    let mut id = NEXT_ID.load(deps.storage)?;
+    let id = NEXT_ID.load(deps.storage)?;
     let tr = Transfer {
         id,
-        sender: info.sender.clone(),
-        recipient: rcpt.clone(),
-        amount: amount.clone(),
-        remark,
-        expiry_ts,
+        sender: sender.clone(),
+        recipient: rcpt,
+        recipient_alias,
+        asset,
+        remark: terms.remark,
+        expiry_ts: terms.expiry_ts,
+        hashlock: terms.hashlock,
+        arbiter,
+        cancel_until,
         status: TransferStatus::Open,
     };
-    TRANSFERS.save(deps.storage, id, &tr)?;
-    id += 1;
-    NEXT_ID.save(deps.storage, &id)?;
+    transfers().save(deps.storage, id, &tr)?;
+    NEXT_ID.save(deps.storage, &(id + 1))?;
+    Ok(tr)
+}
+
+/// Returns the recipient address, plus the username when addressed by alias.
+fn resolve_recipient(deps: Deps, recipient: Recipient) -> Result<(Addr, Option<String>), ContractError> {
+    match recipient {
+        Recipient::Address(addr) => Ok((deps.api.addr_validate(&addr)?, None)),
+        Recipient::Alias(username) => {
+            let alias_contract = CONFIG.load(deps.storage)?.alias_contract.ok_or(AliasNotConfigured)?;
+            let resp: AliasResolveResp = deps
+                .querier
+                .query_wasm_smart(alias_contract, &AliasQueryMsg::Resolve { username: username.clone() })
+                .map_err(|_| AliasNotFound { username: username.clone() })?;
+            Ok((deps.api.addr_validate(&resp.address)?, Some(username)))
+        }
+    }
+}
+
+/// A settled transfer whose funds still have to be sent to `payee`.
+struct Settlement {
+    payee: Addr,
+    asset: Asset,
+    event: Event,
+}
+
+fn send_asset(to: &Addr, asset: Asset) -> StdResult<CosmosMsg> {
+    Ok(match asset {
+        Asset::Native(amount) => BankMsg::Send { to_address: to.to_string(), amount }.into(),
+        Asset::Cw20 { contract, amount } => WasmMsg::Execute {
+            contract_addr: contract.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer { recipient: to.to_string(), amount })?,
+            funds: vec![],
+        }
+        .into(),
+    })
+}
+
+fn exec_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    preimage: Option<Binary>,
+    to: Option<String>,
+) -> Result<Response, ContractError> {
+    let s = settle_claim(deps, &env, &info.sender, id, preimage, to)?;
 
     Ok(Response::new()
-        .add_event(evt_create(tr.id, tr.sender.as_str(), tr.recipient.as_str(), &amount))
-        .add_attribute("action", "create_transfer"))
+        .add_message(send_asset(&s.payee, s.asset)?)
+        .add_event(s.event)
+        .add_attribute("action", "claim_transfer"))
 }
 
-fn exec_claim(deps: DepsMut, env: Env, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
-    let mut tr = TRANSFERS.load(deps.storage, id).map_err(|_| NotFound)?;
+fn settle_claim(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    id: u64,
+    preimage: Option<Binary>,
+    to: Option<String>,
+) -> Result<Settlement, ContractError> {
+    let mut tr = transfers().load(deps.storage, id).map_err(|_| NotFound)?;
     ensure!(matches!(tr.status, TransferStatus::Open), AlreadyFinalized);
 
     // expiry check
@@ -96,121 +283,614 @@ fn exec_claim(deps: DepsMut, env: Env, info: MessageInfo, id: u64) -> Result<Res
         ensure!(Timestamp::from_seconds(ts) > env.block.time, Expired);
     }
 
-    ensure!(info.sender == tr.recipient, NotRecipient);
+    // Arbitrated transfers are released through ApproveTransfer, hash-locked
+    // transfers are claimable by whoever knows the preimage, everything else
+    // only by the recipient.
+    ensure!(tr.arbiter.is_none(), ArbiterRequired);
+    ensure_cancel_window_closed(&tr, env)?;
+    match &tr.hashlock {
+        Some(hashlock) => {
+            let preimage = preimage.as_ref().ok_or(InvalidPreimage)?;
+            ensure!(Sha256::digest(preimage.as_slice()).as_slice() == hashlock.as_slice(), InvalidPreimage);
+        }
+        None => ensure!(*sender == tr.recipient, NotRecipient),
+    }
+    let payee = match to {
+        Some(to) => deps.api.addr_validate(&to)?,
+        None => tr.recipient.clone(),
+    };
 
     tr.status = TransferStatus::Claimed;
-    TRANSFERS.save(deps.storage, id, &tr)?;
+    transfers().save(deps.storage, id, &tr)?;
 
-    let bank = BankMsg::Send { to_address: tr.recipient.to_string(), amount: vec![tr.amount.clone()] };
+    let cfg = CONFIG.load(deps.storage)?;
+    let (payout, fee) = take_fee(deps.storage, &tr.asset, cfg.fee_bps)?;
+    let event = evt_claim(tr.id, payee.as_str(), &payout, &fee, tr.hashlock.as_ref().and(preimage.as_ref()));
 
-    Ok(Response::new()
-        .add_message(bank)
-        .add_event(evt_claim(tr.id, tr.recipient.as_str()))
-        .add_attribute("action", "claim_transfer"))
+    Ok(Settlement { payee, asset: payout, event })
+}
+
+/// Splits `asset` into the payout and the protocol fee, crediting the fee to
+/// `FEES` or `CW20_FEES`.
+fn take_fee(storage: &mut dyn Storage, asset: &Asset, fee_bps: u16) -> Result<(Asset, Asset), ContractError> {
+    match asset {
+        Asset::Native(amount) => {
+            let mut payout = Vec::with_capacity(amount.len());
+            let mut fees = Vec::with_capacity(amount.len());
+            for coin in amount {
+                let fee = calculate_fee(coin.amount, fee_bps);
+                if !fee.is_zero() {
+                    FEES.update(storage, &coin.denom, |bal| -> StdResult<_> { Ok(bal.unwrap_or_default() + fee) })?;
+                }
+                payout.push(Coin { denom: coin.denom.clone(), amount: coin.amount - fee });
+                fees.push(Coin { denom: coin.denom.clone(), amount: fee });
+            }
+            Ok((Asset::Native(payout), Asset::Native(fees)))
+        }
+        Asset::Cw20 { contract, amount } => {
+            let fee = calculate_fee(*amount, fee_bps);
+            if !fee.is_zero() {
+                CW20_FEES.update(storage, contract, |bal| -> StdResult<_> { Ok(bal.unwrap_or_default() + fee) })?;
+            }
+            Ok((
+                Asset::Cw20 { contract: contract.clone(), amount: *amount - fee },
+                Asset::Cw20 { contract: contract.clone(), amount: fee },
+            ))
+        }
+    }
 }
 
 fn exec_refund(deps: DepsMut, env: Env, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
-    let mut tr = TRANSFERS.load(deps.storage, id).map_err(|_| NotFound)?;
+    let s = settle_refund(deps.storage, &env, &info.sender, id)?;
+
+    Ok(Response::new()
+        .add_message(send_asset(&s.payee, s.asset)?)
+        .add_event(s.event)
+        .add_attribute("action", "refund_transfer"))
+}
+
+fn settle_refund(storage: &mut dyn Storage, env: &Env, sender: &Addr, id: u64) -> Result<Settlement, ContractError> {
+    let mut tr = transfers().load(storage, id).map_err(|_| NotFound)?;
     ensure!(matches!(tr.status, TransferStatus::Open), AlreadyFinalized);
 
-    // If there's expiry, it must be expired
-    if let Some(ts) = tr.expiry_ts {
-        ensure!(Timestamp::from_seconds(ts) <= env.block.time, NotExpired);
-    }
+    // Only expired transfers can be refunded; without an expiry the sender has
+    // to use the cancel window instead of racing the recipient.
+    let expired = tr.expiry_ts.is_some_and(|ts| Timestamp::from_seconds(ts) <= env.block.time);
+    ensure!(expired, NotExpired);
     // The sender must be the one to refund
-    ensure!(info.sender == tr.sender, NotSender);
+    ensure!(*sender == tr.sender, NotSender);
 
     tr.status = TransferStatus::Refunded;
-    TRANSFERS.save(deps.storage, id, &tr)?;
+    transfers().save(storage, id, &tr)?;
+
+    let event = evt_refund(tr.id, tr.sender.as_str(), &tr.asset);
+    Ok(Settlement { payee: tr.sender, asset: tr.asset, event })
+}
+
+fn ensure_cancel_window_closed(tr: &Transfer, env: &Env) -> Result<(), ContractError> {
+    if let Some(until) = tr.cancel_until {
+        ensure!(env.block.time.seconds() >= until, CancelWindowOpen);
+    }
+    Ok(())
+}
+
+fn exec_cancel(deps: DepsMut, env: Env, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    let mut tr = transfers().load(deps.storage, id).map_err(|_| NotFound)?;
+    ensure!(matches!(tr.status, TransferStatus::Open), AlreadyFinalized);
+    ensure!(info.sender == tr.sender, NotSender);
+    let cancellable = tr.cancel_until.is_some_and(|until| env.block.time.seconds() < until);
+    ensure!(cancellable, CancelWindowClosed);
+
+    tr.status = TransferStatus::Cancelled;
+    transfers().save(deps.storage, id, &tr)?;
+
+    Ok(Response::new()
+        .add_event(evt_cancel(tr.id, tr.sender.as_str(), &tr.asset))
+        .add_message(send_asset(&tr.sender, tr.asset)?)
+        .add_attribute("action", "cancel_transfer"))
+}
+
+fn exec_approve(deps: DepsMut, env: Env, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    let mut tr = transfers().load(deps.storage, id).map_err(|_| NotFound)?;
+    ensure!(matches!(tr.status, TransferStatus::Open), AlreadyFinalized);
+    ensure!(tr.arbiter.as_ref() == Some(&info.sender), NotArbiter);
+
+    // Once expired the sender's refund takes precedence
+    if let Some(ts) = tr.expiry_ts {
+        ensure!(Timestamp::from_seconds(ts) > env.block.time, Expired);
+    }
+    ensure_cancel_window_closed(&tr, &env)?;
+
+    tr.status = TransferStatus::Claimed;
+    transfers().save(deps.storage, id, &tr)?;
+
+    let cfg = CONFIG.load(deps.storage)?;
+    let (payout, fee) = take_fee(deps.storage, &tr.asset, cfg.fee_bps)?;
+
+    Ok(Response::new()
+        .add_event(evt_approve(tr.id, info.sender.as_str(), tr.recipient.as_str(), &payout, &fee))
+        .add_message(send_asset(&tr.recipient, payout)?)
+        .add_attribute("action", "approve_transfer"))
+}
+
+fn exec_reject(deps: DepsMut, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    let mut tr = transfers().load(deps.storage, id).map_err(|_| NotFound)?;
+    ensure!(matches!(tr.status, TransferStatus::Open), AlreadyFinalized);
+    // Recipients can always bounce a payment they don't want
+    ensure!(info.sender == tr.recipient || tr.arbiter.as_ref() == Some(&info.sender), Unauthorized);
+
+    tr.status = TransferStatus::Rejected;
+    transfers().save(deps.storage, id, &tr)?;
+
+    Ok(Response::new()
+        .add_event(evt_reject(tr.id, info.sender.as_str(), tr.sender.as_str(), &tr.asset))
+        .add_message(send_asset(&tr.sender, tr.asset)?)
+        .add_attribute("action", "reject_transfer"))
+}
+
+fn exec_batch_claim(mut deps: DepsMut, env: Env, info: MessageInfo, ids: Vec<u64>) -> Result<Response, ContractError> {
+    ensure!(!ids.is_empty(), EmptyBatch);
+    let mut settlements = Vec::with_capacity(ids.len());
+    for id in ids {
+        let s = settle_claim(deps.branch(), &env, &info.sender, id, None, None)
+            .map_err(|e| BatchTransferFailed { id, reason: e.to_string() })?;
+        settlements.push(s);
+    }
+    payout_settlements(settlements, "batch_claim")
+}
+
+fn exec_batch_refund(deps: DepsMut, env: Env, info: MessageInfo, ids: Vec<u64>) -> Result<Response, ContractError> {
+    ensure!(!ids.is_empty(), EmptyBatch);
+    let mut settlements = Vec::with_capacity(ids.len());
+    for id in ids {
+        let s = settle_refund(deps.storage, &env, &info.sender, id)
+            .map_err(|e| BatchTransferFailed { id, reason: e.to_string() })?;
+        settlements.push(s);
+    }
+    payout_settlements(settlements, "batch_refund")
+}
+
+/// Merges settlements into a single `BankMsg` per payee (and one CW20 transfer per
+/// payee and token), keeping one event per transfer.
+fn payout_settlements(settlements: Vec<Settlement>, action: &str) -> Result<Response, ContractError> {
+    let mut native: BTreeMap<Addr, Coins> = BTreeMap::new();
+    let mut cw20: BTreeMap<(Addr, Addr), Uint128> = BTreeMap::new();
+    let mut res = Response::new();
+    for s in settlements {
+        match s.asset {
+            Asset::Native(amount) => {
+                let owed = native.entry(s.payee).or_default();
+                for coin in amount {
+                    owed.add(coin)?;
+                }
+            }
+            Asset::Cw20 { contract, amount } => {
+                *cw20.entry((s.payee, contract)).or_default() += amount;
+            }
+        }
+        res = res.add_event(s.event);
+    }
+    for (payee, amount) in native {
+        res = res.add_message(send_asset(&payee, Asset::Native(amount.into_vec()))?);
+    }
+    for ((payee, contract), amount) in cw20 {
+        res = res.add_message(send_asset(&payee, Asset::Cw20 { contract, amount })?);
+    }
+    Ok(res.add_attribute("action", action))
+}
+
+fn exec_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    fee_bps: Option<u16>,
+    alias_contract: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+    ensure!(info.sender == cfg.admin, Unauthorized);
+
+    if let Some(fee_bps) = fee_bps {
+        validate_fee_bps(fee_bps).map_err(|_| InvalidFee)?;
+        cfg.fee_bps = fee_bps;
+    }
+    if let Some(alias_contract) = alias_contract {
+        cfg.alias_contract = Some(deps.api.addr_validate(&alias_contract)?);
+    }
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_config")
+        .add_attribute("fee_bps", cfg.fee_bps.to_string()))
+}
+
+fn exec_withdraw_fees(deps: DepsMut, info: MessageInfo, to: String) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    ensure!(info.sender == cfg.admin, Unauthorized);
+    let to = deps.api.addr_validate(&to)?;
+
+    let mut fees = vec![];
+    let native: Vec<Coin> = FEES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+        .collect::<StdResult<_>>()?;
+    if !native.is_empty() {
+        fees.push(Asset::Native(native));
+    }
+    for item in CW20_FEES.range(deps.storage, None, None, Order::Ascending) {
+        let (contract, amount) = item?;
+        fees.push(Asset::Cw20 { contract, amount });
+    }
+    ensure!(!fees.is_empty(), NoFees);
+    FEES.clear(deps.storage);
+    CW20_FEES.clear(deps.storage);
+
+    let evt = evt_withdraw_fees(to.as_str(), &fees);
+    let msgs = fees.into_iter().map(|asset| send_asset(&to, asset)).collect::<StdResult<Vec<_>>>()?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_event(evt)
+        .add_attribute("action", "withdraw_fees"))
+}
+
+fn exec_create_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    rate_per_second: Uint128,
+    start: u64,
+    end: u64,
+) -> Result<Response, ContractError> {
+    let rcpt = deps.api.addr_validate(&recipient)?;
+    ensure!(start >= env.block.time.seconds() && end > start, InvalidStreamSchedule);
+    ensure!(!rate_per_second.is_zero(), InvalidStreamSchedule);
+
+    // The whole stream is funded up-front in a single denom
+    let deposit = rate_per_second.checked_mul(Uint128::from(end - start)).map_err(StdError::from)?;
+    ensure!(info.funds.len() == 1, InvalidFunds);
+    let funds = info.funds[0].clone();
+    ensure!(funds.amount == deposit, InvalidFunds);
+
+    let id = NEXT_STREAM_ID.load(deps.storage)?;
+    let stream = Stream {
+        id,
+        sender: info.sender.clone(),
+        recipient: rcpt,
+        denom: funds.denom.clone(),
+        rate_per_second,
+        start,
+        end,
+        deposit,
+        withdrawn: Uint128::zero(),
+        status: StreamStatus::Active,
+    };
+    streams().save(deps.storage, id, &stream)?;
+    NEXT_STREAM_ID.save(deps.storage, &(id + 1))?;
+
+    Ok(Response::new()
+        .add_event(evt_create_stream(id, stream.sender.as_str(), stream.recipient.as_str(), &funds, start, end))
+        .add_attribute("action", "create_stream"))
+}
+
+fn exec_withdraw_stream(deps: DepsMut, env: Env, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    let mut stream = streams().load(deps.storage, id).map_err(|_| StreamNotFound)?;
+    ensure!(matches!(stream.status, StreamStatus::Active), StreamNotActive);
+    ensure!(info.sender == stream.recipient, NotRecipient);
+
+    let amount = stream.withdrawable(env.block.time.seconds());
+    ensure!(!amount.is_zero(), NothingToWithdraw);
+
+    stream.withdrawn += amount;
+    if stream.withdrawn == stream.deposit {
+        stream.status = StreamStatus::Completed;
+    }
+    streams().save(deps.storage, id, &stream)?;
 
-    let bank = BankMsg::Send { to_address: tr.sender.to_string(), amount: vec![tr.amount.clone()] };
+    let coin = Coin { denom: stream.denom.clone(), amount };
+    let bank = BankMsg::Send { to_address: stream.recipient.to_string(), amount: vec![coin.clone()] };
 
     Ok(Response::new()
         .add_message(bank)
-        .add_event(evt_refund(tr.id, tr.sender.as_str()))
-        .add_attribute("action", "refund_transfer"))
+        .add_event(evt_withdraw_stream(id, stream.recipient.as_str(), &coin))
+        .add_attribute("action", "withdraw_stream"))
+}
+
+fn exec_cancel_stream(deps: DepsMut, env: Env, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    let mut stream = streams().load(deps.storage, id).map_err(|_| StreamNotFound)?;
+    ensure!(matches!(stream.status, StreamStatus::Active), StreamNotActive);
+    ensure!(info.sender == stream.sender, NotSender);
+
+    // Whatever has vested still belongs to the recipient
+    let now = env.block.time.seconds();
+    let paid = stream.withdrawable(now);
+    let refunded = stream.deposit - stream.vested(now);
+
+    stream.withdrawn += paid;
+    stream.status = StreamStatus::Cancelled;
+    streams().save(deps.storage, id, &stream)?;
+
+    let mut res = Response::new();
+    if !paid.is_zero() {
+        res = res.add_message(BankMsg::Send {
+            to_address: stream.recipient.to_string(),
+            amount: vec![Coin { denom: stream.denom.clone(), amount: paid }],
+        });
+    }
+    if !refunded.is_zero() {
+        res = res.add_message(BankMsg::Send {
+            to_address: stream.sender.to_string(),
+            amount: vec![Coin { denom: stream.denom.clone(), amount: refunded }],
+        });
+    }
+
+    Ok(res
+        .add_event(evt_cancel_stream(id, stream.sender.as_str(), paid, refunded))
+        .add_attribute("action", "cancel_stream"))
+}
+
+fn exec_deposit_voucher_funds(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let funds = normalize_coins(&info.funds)?;
+    for coin in &funds {
+        VOUCHER_BALANCES.update(deps.storage, (&info.sender, &coin.denom), |bal| -> StdResult<_> {
+            Ok(bal.unwrap_or_default() + coin.amount)
+        })?;
+    }
+
+    Ok(Response::new()
+        .add_event(evt_deposit_voucher_funds(info.sender.as_str(), &funds))
+        .add_attribute("action", "deposit_voucher_funds"))
+}
+
+fn exec_set_voucher_key(deps: DepsMut, info: MessageInfo, pubkey: Binary) -> Result<Response, ContractError> {
+    ensure!(pubkey.len() == 33 || pubkey.len() == 65, InvalidVoucherKey);
+    VOUCHER_KEYS.save(deps.storage, &info.sender, &pubkey)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_voucher_key")
+        .add_attribute("sender", info.sender.to_string()))
+}
+
+fn exec_withdraw_voucher_funds(deps: DepsMut, info: MessageInfo, amount: Vec<Coin>) -> Result<Response, ContractError> {
+    let amount = normalize_coins(&amount)?;
+    for coin in &amount {
+        debit_voucher_balance(deps.storage, &info.sender, coin)?;
+    }
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send { to_address: info.sender.to_string(), amount: amount.clone() })
+        .add_event(evt_withdraw_voucher_funds(info.sender.as_str(), &amount))
+        .add_attribute("action", "withdraw_voucher_funds"))
+}
+
+fn exec_redeem_voucher(deps: DepsMut, env: Env, voucher: Voucher, signature: Binary) -> Result<Response, ContractError> {
+    ensure!(
+        voucher.chain_id == env.block.chain_id && voucher.contract == env.contract.address.as_str(),
+        WrongVoucherDomain
+    );
+    let sender = deps.api.addr_validate(&voucher.sender)?;
+    let recipient = deps.api.addr_validate(&voucher.recipient)?;
+    ensure!(!voucher.amount.amount.is_zero(), InvalidFunds);
+    if let Some(expiry) = voucher.expiry_ts {
+        ensure!(env.block.time.seconds() < expiry, Expired);
+    }
+    ensure!(
+        !USED_NONCES.has(deps.storage, (&sender, voucher.nonce)),
+        NonceUsed { nonce: voucher.nonce }
+    );
+
+    let pubkey = VOUCHER_KEYS.may_load(deps.storage, &sender)?.ok_or(VoucherKeyNotSet)?;
+    let hash = Sha256::digest(to_json_vec(&voucher)?);
+    let valid = deps.api.secp256k1_verify(&hash, &signature, &pubkey).unwrap_or(false);
+    ensure!(valid, InvalidVoucherSignature);
+
+    USED_NONCES.save(deps.storage, (&sender, voucher.nonce), &Empty {})?;
+    debit_voucher_balance(deps.storage, &sender, &voucher.amount)?;
+
+    let cfg = CONFIG.load(deps.storage)?;
+    let (payout, fee) = take_fee(deps.storage, &Asset::Native(vec![voucher.amount]), cfg.fee_bps)?;
+    let event = evt_redeem_voucher(sender.as_str(), recipient.as_str(), voucher.nonce, &payout, &fee);
+
+    Ok(Response::new()
+        .add_message(send_asset(&recipient, payout)?)
+        .add_event(event)
+        .add_attribute("action", "redeem_voucher"))
+}
+
+fn debit_voucher_balance(storage: &mut dyn Storage, sender: &Addr, coin: &Coin) -> Result<(), ContractError> {
+    let key = (sender, coin.denom.as_str());
+    let bal = VOUCHER_BALANCES.may_load(storage, key)?.unwrap_or_default();
+    ensure!(bal >= coin.amount, InsufficientVoucherBalance);
+    if bal == coin.amount {
+        VOUCHER_BALANCES.remove(storage, key);
+    } else {
+        VOUCHER_BALANCES.save(storage, key, &(bal - coin.amount))?;
+    }
+    Ok(())
 }
 
-#[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => {
             let c = CONFIG.load(deps.storage)?;
-            to_json_binary(&ConfigResp { admin: c.admin.to_string(), default_denom: c.default_denom })
+            to_json_binary(&ConfigResp {
+                admin: c.admin.to_string(),
+                default_denom: c.default_denom,
+                fee_bps: c.fee_bps,
+                alias_contract: c.alias_contract.map(|a| a.to_string()),
+            })
+        }
+        QueryMsg::FeeBalances {} => {
+            let fees = FEES
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+                .collect::<StdResult<_>>()?;
+            let cw20_fees = CW20_FEES
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(contract, amount)| Cw20Coin { address: contract.to_string(), amount }))
+                .collect::<StdResult<_>>()?;
+            to_json_binary(&FeeBalancesResp { fees, cw20_fees })
         }
         QueryMsg::GetTransfer { id } => {
-            let t = TRANSFERS.load(deps.storage, id)?;
-            to_json_binary(&super::msg::TransferResp{
-                id: t.id,
-                sender: t.sender.to_string(),
-                recipient: t.recipient.to_string(),
-                amount: t.amount,
-                remark: t.remark,
-                expiry_ts: t.expiry_ts,
-                status: format!("{:?}", t.status),
-            })
+            let t = transfers().load(deps.storage, id)?;
+            to_json_binary(&transfer_resp(t))
         }
         QueryMsg::ListBySender { sender, start_after, limit } => {
             let sender_addr = deps.api.addr_validate(&sender)?;
             let limit = limit.unwrap_or(10).min(30) as usize;
             let start = start_after.map(Bound::exclusive);
-            
-            let transfers: StdResult<Vec<_>> = TRANSFERS
+
+            let transfers: StdResult<Vec<_>> = transfers()
+                .idx
+                .sender
+                .prefix(sender_addr)
                 .range(deps.storage, start, None, Order::Ascending)
-                .filter(|item| {
-                    if let Ok((_, transfer)) = item {
-                        transfer.sender == sender_addr
-                    } else {
-                        false
-                    }
-                })
                 .take(limit)
-                .map(|item| {
-                    let (_, transfer) = item?;
-                    Ok(super::msg::TransferResp {
-                        id: transfer.id,
-                        sender: transfer.sender.to_string(),
-                        recipient: transfer.recipient.to_string(),
-                        amount: transfer.amount,
-                        remark: transfer.remark,
-                        expiry_ts: transfer.expiry_ts,
-                        status: format!("{:?}", transfer.status),
-                    })
-                })
+                .map(|item| item.map(|(_, t)| transfer_resp(t)))
                 .collect();
-            
+
             to_json_binary(&transfers?)
         }
         QueryMsg::ListByRecipient { recipient, start_after, limit } => {
             let recipient_addr = deps.api.addr_validate(&recipient)?;
             let limit = limit.unwrap_or(10).min(30) as usize;
             let start = start_after.map(Bound::exclusive);
-            
-            let transfers: StdResult<Vec<_>> = TRANSFERS
+
+            let transfers: StdResult<Vec<_>> = transfers()
+                .idx
+                .recipient
+                .prefix(recipient_addr)
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| item.map(|(_, t)| transfer_resp(t)))
+                .collect();
+
+            to_json_binary(&transfers?)
+        }
+        QueryMsg::ListByStatus { status, start_after, limit } => {
+            let limit = limit.unwrap_or(10).min(30) as usize;
+            let start = start_after.map(Bound::exclusive);
+
+            let transfers: StdResult<Vec<_>> = transfers()
+                .idx
+                .status
+                .prefix(status.as_str().to_string())
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| item.map(|(_, t)| transfer_resp(t)))
+                .collect();
+
+            to_json_binary(&transfers?)
+        }
+        QueryMsg::ListOpenByRecipient { recipient, start_after, limit } => {
+            let recipient_addr = deps.api.addr_validate(&recipient)?;
+            let limit = limit.unwrap_or(10).min(30) as usize;
+            let start = start_after.map(Bound::exclusive);
+
+            let transfers: StdResult<Vec<_>> = transfers()
+                .idx
+                .recipient_status
+                .prefix((recipient_addr, TransferStatus::Open.as_str().to_string()))
                 .range(deps.storage, start, None, Order::Ascending)
-                .filter(|item| {
-                    if let Ok((_, transfer)) = item {
-                        transfer.recipient == recipient_addr
-                    } else {
-                        false
-                    }
-                })
                 .take(limit)
-                .map(|item| {
-                    let (_, transfer) = item?;
-                    Ok(super::msg::TransferResp {
-                        id: transfer.id,
-                        sender: transfer.sender.to_string(),
-                        recipient: transfer.recipient.to_string(),
-                        amount: transfer.amount,
-                        remark: transfer.remark,
-                        expiry_ts: transfer.expiry_ts,
-                        status: format!("{:?}", transfer.status),
-                    })
-                })
+                .map(|item| item.map(|(_, t)| transfer_resp(t)))
                 .collect();
-            
+
             to_json_binary(&transfers?)
         }
+        QueryMsg::GetStream { id } => {
+            let s = streams().load(deps.storage, id)?;
+            to_json_binary(&stream_resp(s, env.block.time.seconds()))
+        }
+        QueryMsg::ListStreamsBySender { sender, start_after, limit } => {
+            let sender_addr = deps.api.addr_validate(&sender)?;
+            let limit = limit.unwrap_or(10).min(30) as usize;
+            let start = start_after.map(Bound::exclusive);
+
+            let streams: StdResult<Vec<_>> = streams()
+                .idx
+                .sender
+                .prefix(sender_addr)
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| item.map(|(_, s)| stream_resp(s, env.block.time.seconds())))
+                .collect();
+
+            to_json_binary(&streams?)
+        }
+        QueryMsg::ListStreamsByRecipient { recipient, start_after, limit } => {
+            let recipient_addr = deps.api.addr_validate(&recipient)?;
+            let limit = limit.unwrap_or(10).min(30) as usize;
+            let start = start_after.map(Bound::exclusive);
+
+            let streams: StdResult<Vec<_>> = streams()
+                .idx
+                .recipient
+                .prefix(recipient_addr)
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| item.map(|(_, s)| stream_resp(s, env.block.time.seconds())))
+                .collect();
+
+            to_json_binary(&streams?)
+        }
+        QueryMsg::VoucherAccount { sender } => {
+            let sender_addr = deps.api.addr_validate(&sender)?;
+            let balance = VOUCHER_BALANCES
+                .prefix(&sender_addr)
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+                .collect::<StdResult<_>>()?;
+            let pubkey = VOUCHER_KEYS.may_load(deps.storage, &sender_addr)?;
+            to_json_binary(&VoucherAccountResp { sender, pubkey, balance })
+        }
+        QueryMsg::UsedNonces { sender, start_after, limit } => {
+            let sender_addr = deps.api.addr_validate(&sender)?;
+            let limit = limit.unwrap_or(10).min(30) as usize;
+            let start = start_after.map(Bound::exclusive);
+
+            let nonces: StdResult<Vec<u64>> = USED_NONCES
+                .prefix(&sender_addr)
+                .keys(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .collect();
+
+            to_json_binary(&nonces?)
+        }
+    }
+}
+
+fn transfer_resp(t: Transfer) -> TransferResp {
+    TransferResp {
+        id: t.id,
+        sender: t.sender.to_string(),
+        recipient: t.recipient.to_string(),
+        recipient_alias: t.recipient_alias,
+        asset: t.asset,
+        remark: t.remark,
+        expiry_ts: t.expiry_ts,
+        hashlock: t.hashlock,
+        arbiter: t.arbiter.map(|a| a.to_string()),
+        cancel_until: t.cancel_until,
+        status: t.status.as_str().to_string(),
     }
-}
\ No newline at end of file
+}
+
+fn stream_resp(s: Stream, now: u64) -> StreamResp {
+    StreamResp {
+        id: s.id,
+        sender: s.sender.to_string(),
+        recipient: s.recipient.to_string(),
+        denom: s.denom.clone(),
+        rate_per_second: s.rate_per_second,
+        start: s.start,
+        end: s.end,
+        deposit: s.deposit,
+        withdrawn: s.withdrawn,
+        withdrawable: match s.status {
+            StreamStatus::Active => s.withdrawable(now),
+            _ => Uint128::zero(),
+        },
+        status: s.status,
+    }
+}
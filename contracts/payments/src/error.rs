@@ -12,6 +12,36 @@ pub enum ContractError {
     #[error("Invalid funds")]
     InvalidFunds,
 
+    #[error("Invalid fee: must be between 0 and 1000 bps")]
+    InvalidFee,
+
+    #[error("No fees to withdraw")]
+    NoFees,
+
+    #[error("Stream not found")]
+    StreamNotFound,
+
+    #[error("Stream is not active")]
+    StreamNotActive,
+
+    #[error("Invalid stream schedule")]
+    InvalidStreamSchedule,
+
+    #[error("Nothing to withdraw")]
+    NothingToWithdraw,
+
+    #[error("Batch is empty")]
+    EmptyBatch,
+
+    #[error("Batch item {index}: {reason}")]
+    BatchItemFailed { index: usize, reason: String },
+
+    #[error("Transfer {id}: {reason}")]
+    BatchTransferFailed { id: u64, reason: String },
+
+    #[error("Duplicate denom in amount")]
+    DuplicateDenom,
+
     #[error("Transfer not found")]
     NotFound,
 
@@ -21,6 +51,57 @@ pub enum ContractError {
     #[error("Not recipient")]
     NotRecipient,
 
+    #[error("No alias contract configured")]
+    AliasNotConfigured,
+
+    #[error("Alias not found: {username}")]
+    AliasNotFound { username: String },
+
+    #[error("Invalid hashlock: must be a 32 byte SHA-256 hash")]
+    InvalidHashlock,
+
+    #[error("Preimage does not match hashlock")]
+    InvalidPreimage,
+
+    #[error("Invalid cancel window")]
+    InvalidCancelWindow,
+
+    #[error("Transfer is still within its cancel window")]
+    CancelWindowOpen,
+
+    #[error("Cancel window has passed")]
+    CancelWindowClosed,
+
+    #[error("Invalid voucher public key")]
+    InvalidVoucherKey,
+
+    #[error("No voucher key set for sender")]
+    VoucherKeyNotSet,
+
+    #[error("Invalid voucher signature")]
+    InvalidVoucherSignature,
+
+    #[error("Voucher was issued for another chain or contract")]
+    WrongVoucherDomain,
+
+    #[error("Voucher nonce {nonce} already used")]
+    NonceUsed { nonce: u64 },
+
+    #[error("Insufficient voucher balance")]
+    InsufficientVoucherBalance,
+
+    #[error("Not arbiter")]
+    NotArbiter,
+
+    #[error("Transfer must be released by its arbiter")]
+    ArbiterRequired,
+
+    #[error("Arbiter must differ from sender and recipient")]
+    InvalidArbiter,
+
+    #[error("Transfer cannot have both a hashlock and an arbiter")]
+    ConflictingReleaseConditions,
+
     #[error("Not sender")]
     NotSender,
 
@@ -1,21 +1,123 @@
-use cosmwasm_std::{Event, Coin};
+use cosmwasm_std::{Binary, Coin, Event, Uint128};
 
-pub fn evt_create(id: u64, sender: &str, recipient: &str, amount: &Coin) -> Event {
-    Event::new("seimoney.create_transfer")
+use crate::state::Asset;
+
+/// Formats coins as a comma separated list, e.g. `100uusdc,5usei`.
+pub fn fmt_coins(coins: &[Coin]) -> String {
+    coins.iter().map(|c| format!("{}{}", c.amount, c.denom)).collect::<Vec<_>>().join(",")
+}
+
+/// Formats an asset like `fmt_coins`, with CW20 amounts written as `100cw20:<contract>`.
+pub fn fmt_asset(asset: &Asset) -> String {
+    match asset {
+        Asset::Native(coins) => fmt_coins(coins),
+        Asset::Cw20 { contract, amount } => format!("{}cw20:{}", amount, contract),
+    }
+}
+
+pub fn evt_create(id: u64, sender: &str, recipient: &str, recipient_alias: Option<&str>, amount: &Asset) -> Event {
+    let evt = Event::new("seimoney.create_transfer")
         .add_attribute("id", id.to_string())
         .add_attribute("sender", sender.to_string())
         .add_attribute("recipient", recipient.to_string())
-        .add_attribute("amount", format!("{}{}", amount.amount, amount.denom))
+        .add_attribute("amount", fmt_asset(amount));
+    match recipient_alias {
+        Some(alias) => evt.add_attribute("recipient_alias", alias.to_string()),
+        None => evt,
+    }
 }
 
-pub fn evt_claim(id: u64, recipient: &str) -> Event {
-    Event::new("seimoney.claim_transfer")
+pub fn evt_claim(id: u64, recipient: &str, amount: &Asset, fee: &Asset, preimage: Option<&Binary>) -> Event {
+    let evt = Event::new("seimoney.claim_transfer")
         .add_attribute("id", id.to_string())
         .add_attribute("recipient", recipient.to_string())
+        .add_attribute("amount", fmt_asset(amount))
+        .add_attribute("fee", fmt_asset(fee));
+    match preimage {
+        Some(p) => evt.add_attribute("preimage", p.to_base64()),
+        None => evt,
+    }
 }
 
-pub fn evt_refund(id: u64, sender: &str) -> Event {
+pub fn evt_refund(id: u64, sender: &str, amount: &Asset) -> Event {
     Event::new("seimoney.refund_transfer")
         .add_attribute("id", id.to_string())
         .add_attribute("sender", sender.to_string())
+        .add_attribute("amount", fmt_asset(amount))
+}
+
+pub fn evt_cancel(id: u64, sender: &str, amount: &Asset) -> Event {
+    Event::new("seimoney.cancel_transfer")
+        .add_attribute("id", id.to_string())
+        .add_attribute("sender", sender.to_string())
+        .add_attribute("amount", fmt_asset(amount))
+}
+
+pub fn evt_approve(id: u64, arbiter: &str, recipient: &str, amount: &Asset, fee: &Asset) -> Event {
+    Event::new("seimoney.approve_transfer")
+        .add_attribute("id", id.to_string())
+        .add_attribute("arbiter", arbiter.to_string())
+        .add_attribute("recipient", recipient.to_string())
+        .add_attribute("amount", fmt_asset(amount))
+        .add_attribute("fee", fmt_asset(fee))
+}
+
+pub fn evt_reject(id: u64, rejected_by: &str, sender: &str, amount: &Asset) -> Event {
+    Event::new("seimoney.reject_transfer")
+        .add_attribute("id", id.to_string())
+        .add_attribute("rejected_by", rejected_by.to_string())
+        .add_attribute("sender", sender.to_string())
+        .add_attribute("amount", fmt_asset(amount))
+}
+
+pub fn evt_withdraw_fees(to: &str, amount: &[Asset]) -> Event {
+    Event::new("seimoney.withdraw_fees")
+        .add_attribute("to", to.to_string())
+        .add_attribute("amount", amount.iter().map(fmt_asset).collect::<Vec<_>>().join(","))
+}
+
+pub fn evt_deposit_voucher_funds(sender: &str, amount: &[Coin]) -> Event {
+    Event::new("seimoney.deposit_voucher_funds")
+        .add_attribute("sender", sender.to_string())
+        .add_attribute("amount", fmt_coins(amount))
+}
+
+pub fn evt_withdraw_voucher_funds(sender: &str, amount: &[Coin]) -> Event {
+    Event::new("seimoney.withdraw_voucher_funds")
+        .add_attribute("sender", sender.to_string())
+        .add_attribute("amount", fmt_coins(amount))
+}
+
+pub fn evt_redeem_voucher(sender: &str, recipient: &str, nonce: u64, amount: &Asset, fee: &Asset) -> Event {
+    Event::new("seimoney.redeem_voucher")
+        .add_attribute("sender", sender.to_string())
+        .add_attribute("recipient", recipient.to_string())
+        .add_attribute("nonce", nonce.to_string())
+        .add_attribute("amount", fmt_asset(amount))
+        .add_attribute("fee", fmt_asset(fee))
+}
+
+pub fn evt_create_stream(id: u64, sender: &str, recipient: &str, deposit: &Coin, start: u64, end: u64) -> Event {
+    Event::new("seimoney.create_stream")
+        .add_attribute("id", id.to_string())
+        .add_attribute("sender", sender.to_string())
+        .add_attribute("recipient", recipient.to_string())
+        .add_attribute("deposit", format!("{}{}", deposit.amount, deposit.denom))
+        .add_attribute("start", start.to_string())
+        .add_attribute("end", end.to_string())
+}
+
+pub fn evt_withdraw_stream(id: u64, recipient: &str, amount: &Coin) -> Event {
+    Event::new("seimoney.withdraw_stream")
+        .add_attribute("id", id.to_string())
+        .add_attribute("recipient", recipient.to_string())
+        .add_attribute("amount", format!("{}{}", amount.amount, amount.denom))
+}
+
+pub fn evt_cancel_stream(id: u64, sender: &str, paid: Uint128, refunded: Uint128) -> Event {
+    Event::new("seimoney.cancel_stream")
+        .add_attribute("id", id.to_string())
+        .add_attribute("sender", sender.to_string())
+        .add_attribute("paid", paid.to_string())
+        .add_attribute("refunded", refunded.to_string())
 }
\ No newline at end of file
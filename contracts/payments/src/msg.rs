@@ -1,22 +1,129 @@
-use cosmwasm_std::Coin;
+use cosmwasm_std::{Binary, Coin, Uint128};
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
+use cw20::{Cw20Coin, Cw20ReceiveMsg};
+
+use crate::state::{Asset, StreamStatus, TransferStatus};
+
 #[cw_serde]
 pub struct InstantiateMsg {
     pub default_denom: String,
     pub admin: Option<String>,
+    /// Protocol fee taken from claimed transfers, in basis points (max 1000).
+    pub fee_bps: Option<u16>,
+    /// `seimoney-alias` contract used to resolve `Recipient::Alias`.
+    pub alias_contract: Option<String>,
+}
+
+#[cw_serde]
+pub enum Recipient {
+    Address(String),
+    /// Username registered in the configured alias contract.
+    Alias(String),
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
     CreateTransfer {
-        recipient: String,
-        amount: Coin,  // Added missing amount parameter
+        recipient: Recipient,
+        /// Exact coins attached to the message; several denoms may be escrowed at once.
+        amount: Vec<Coin>,
         remark: Option<String>,
         expiry_ts: Option<u64>,
+        /// SHA-256 hash of a secret; when set, anyone presenting the preimage can claim.
+        hashlock: Option<Binary>,
+        /// When set, funds only move once this address approves or rejects the transfer.
+        arbiter: Option<String>,
+        /// Seconds after creation during which the sender may cancel and the
+        /// recipient cannot yet claim.
+        cancel_window_secs: Option<u64>,
+    },
+    ClaimTransfer {
+        id: u64,
+        /// Required for hash-locked transfers.
+        preimage: Option<Binary>,
+        /// Payout address, defaults to the stored recipient.
+        to: Option<String>,
     },
-    ClaimTransfer { id: u64 },
+    /// Sender only, once `expiry_ts` has passed.
     RefundTransfer { id: u64 },
+    /// Sender only, while the transfer's cancel window is open.
+    CancelTransfer { id: u64 },
+    /// Arbiter only: releases the funds to the recipient.
+    ApproveTransfer { id: u64 },
+    /// Arbiter or recipient: bounces the funds back to the sender.
+    RejectTransfer { id: u64 },
+    /// CW20 `Send` hook; `msg` must decode to a `ReceiveMsg`.
+    Receive(Cw20ReceiveMsg),
+    /// Creates one transfer per item; the attached funds must equal the sum of all amounts.
+    BatchCreateTransfer { items: Vec<TransferItem> },
+    /// Claims every listed transfer, failing the whole batch if any one cannot be claimed.
+    BatchClaim { ids: Vec<u64> },
+    /// Refunds every listed transfer, failing the whole batch if any one cannot be refunded.
+    BatchRefund { ids: Vec<u64> },
+    /// Admin only.
+    UpdateConfig { fee_bps: Option<u16>, alias_contract: Option<String> },
+    /// Admin only: sends all collected fees to `to`.
+    WithdrawFees { to: String },
+    /// Streams `rate_per_second` to `recipient` between `start` and `end` (unix seconds).
+    /// The full `rate_per_second * (end - start)` must be attached in a single denom.
+    CreateStream {
+        recipient: String,
+        rate_per_second: Uint128,
+        start: u64,
+        end: u64,
+    },
+    /// Recipient only: pays out everything vested so far.
+    WithdrawStream { id: u64 },
+    /// Sender only: pays the recipient what has vested and refunds the rest.
+    CancelStream { id: u64 },
+    /// Adds the attached coins to the sender's voucher balance.
+    DepositVoucherFunds {},
+    /// Sets the secp256k1 public key (33 or 65 bytes) the sender's vouchers are signed with.
+    SetVoucherKey { pubkey: Binary },
+    /// Returns unspent voucher funds to the sender.
+    WithdrawVoucherFunds { amount: Vec<Coin> },
+    /// Pays out a voucher signed off-chain by its sender. Anyone may submit it.
+    RedeemVoucher { voucher: Voucher, signature: Binary },
+}
+
+/// Off-chain cheque drawn on `sender`'s voucher balance.
+/// The signature covers `sha256` of the voucher's JSON encoding, fields in the order below.
+/// `chain_id` and `contract` bind it to a single deployment so it cannot be replayed elsewhere.
+#[cw_serde]
+pub struct Voucher {
+    pub chain_id: String,
+    pub contract: String,
+    pub sender: String,
+    pub recipient: String,
+    pub amount: Coin,
+    pub nonce: u64,
+    pub expiry_ts: Option<u64>,
+}
+
+/// Messages accepted in the `msg` field of a CW20 `Send` to this contract.
+#[cw_serde]
+pub enum ReceiveMsg {
+    /// Escrows the sent tokens, with the same semantics as `ExecuteMsg::CreateTransfer`.
+    CreateTransfer {
+        recipient: Recipient,
+        remark: Option<String>,
+        expiry_ts: Option<u64>,
+        hashlock: Option<Binary>,
+        arbiter: Option<String>,
+        cancel_window_secs: Option<u64>,
+    },
+}
+
+#[cw_serde]
+pub struct TransferItem {
+    pub recipient: Recipient,
+    pub amount: Vec<Coin>,
+    pub remark: Option<String>,
+    pub expiry_ts: Option<u64>,
+    pub hashlock: Option<Binary>,
+    pub arbiter: Option<String>,
+    pub cancel_window_secs: Option<u64>,
 }
 
 #[cw_serde]
@@ -24,18 +131,45 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     #[returns(ConfigResp)]
     Config {},
+    #[returns(FeeBalancesResp)]
+    FeeBalances {},
     #[returns(TransferResp)]
     GetTransfer { id: u64 },
     #[returns(Vec<TransferResp>)]
     ListBySender { sender: String, start_after: Option<u64>, limit: Option<u32> },
     #[returns(Vec<TransferResp>)]
     ListByRecipient { recipient: String, start_after: Option<u64>, limit: Option<u32> },
+    #[returns(Vec<TransferResp>)]
+    ListByStatus { status: TransferStatus, start_after: Option<u64>, limit: Option<u32> },
+    /// Transfers still waiting to be claimed by `recipient`.
+    #[returns(Vec<TransferResp>)]
+    ListOpenByRecipient { recipient: String, start_after: Option<u64>, limit: Option<u32> },
+    #[returns(StreamResp)]
+    GetStream { id: u64 },
+    #[returns(Vec<StreamResp>)]
+    ListStreamsBySender { sender: String, start_after: Option<u64>, limit: Option<u32> },
+    #[returns(Vec<StreamResp>)]
+    ListStreamsByRecipient { recipient: String, start_after: Option<u64>, limit: Option<u32> },
+    /// Voucher key and available balance of `sender`.
+    #[returns(VoucherAccountResp)]
+    VoucherAccount { sender: String },
+    /// Nonces of `sender`'s vouchers that have already been redeemed.
+    #[returns(Vec<u64>)]
+    UsedNonces { sender: String, start_after: Option<u64>, limit: Option<u32> },
 }
 
 #[cw_serde]
 pub struct ConfigResp {
     pub admin: String,
     pub default_denom: String,
+    pub fee_bps: u16,
+    pub alias_contract: Option<String>,
+}
+
+#[cw_serde]
+pub struct FeeBalancesResp {
+    pub fees: Vec<Coin>,
+    pub cw20_fees: Vec<Cw20Coin>,
 }
 
 #[cw_serde]
@@ -43,8 +177,50 @@ pub struct TransferResp {
     pub id: u64,
     pub sender: String,
     pub recipient: String,
-    pub amount: Coin,
+    /// Username the transfer was addressed to, if it was sent to an alias.
+    pub recipient_alias: Option<String>,
+    pub asset: Asset,
     pub remark: Option<String>,
     pub expiry_ts: Option<u64>,
+    pub hashlock: Option<Binary>,
+    pub arbiter: Option<String>,
+    /// End of the sender's cancel window (unix seconds).
+    pub cancel_until: Option<u64>,
     pub status: String,
+}
+
+#[cw_serde]
+pub struct StreamResp {
+    pub id: u64,
+    pub sender: String,
+    pub recipient: String,
+    pub denom: String,
+    pub rate_per_second: Uint128,
+    pub start: u64,
+    pub end: u64,
+    pub deposit: Uint128,
+    pub withdrawn: Uint128,
+    /// Vested but not yet withdrawn, as of the queried block.
+    pub withdrawable: Uint128,
+    pub status: StreamStatus,
+}
+
+#[cw_serde]
+pub struct VoucherAccountResp {
+    pub sender: String,
+    pub pubkey: Option<Binary>,
+    pub balance: Vec<Coin>,
+}
+
+/// Subset of the `seimoney-alias` query interface used to resolve usernames.
+#[cw_serde]
+pub enum AliasQueryMsg {
+    Resolve { username: String },
+}
+
+#[cw_serde]
+pub struct AliasResolveResp {
+    pub username: String,
+    pub address: String,
+    pub registered_at: u64,
 }
\ No newline at end of file
@@ -1,10 +1,12 @@
-use cosmwasm_std::{Addr, Coin};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Binary, Coin, Empty, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
 pub struct Config {
     pub admin: Addr,
     pub default_denom: String,
+    pub fee_bps: u16,
+    pub alias_contract: Option<Addr>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
@@ -12,6 +14,27 @@ pub enum TransferStatus {
     Open,
     Claimed,
     Refunded,
+    Rejected,
+    Cancelled,
+}
+
+impl TransferStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferStatus::Open => "Open",
+            TransferStatus::Claimed => "Claimed",
+            TransferStatus::Refunded => "Refunded",
+            TransferStatus::Rejected => "Rejected",
+            TransferStatus::Cancelled => "Cancelled",
+        }
+    }
+}
+
+/// Escrowed funds: one or more native coins, or a single CW20 token.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+pub enum Asset {
+    Native(Vec<Coin>),
+    Cw20 { contract: Addr, amount: Uint128 },
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
@@ -19,12 +42,110 @@ pub struct Transfer {
     pub id: u64,
     pub sender: Addr,
     pub recipient: Addr,
-    pub amount: Coin,
+    pub recipient_alias: Option<String>,
+    pub asset: Asset,
     pub remark: Option<String>,
     pub expiry_ts: Option<u64>,
+    pub hashlock: Option<Binary>,
+    pub arbiter: Option<Addr>,
+    pub cancel_until: Option<u64>,
     pub status: TransferStatus,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const NEXT_ID: Item<u64> = Item::new("next_id");
-pub const TRANSFERS: Map<u64, Transfer> = Map::new("transfers");
\ No newline at end of file
+/// Collected protocol fees by denom, awaiting `WithdrawFees`.
+pub const FEES: Map<&str, Uint128> = Map::new("fees");
+/// Collected protocol fees by CW20 token contract.
+pub const CW20_FEES: Map<&Addr, Uint128> = Map::new("cw20_fees");
+
+pub struct TransferIndexes<'a> {
+    pub sender: MultiIndex<'a, Addr, Transfer, u64>,
+    pub recipient: MultiIndex<'a, Addr, Transfer, u64>,
+    pub status: MultiIndex<'a, String, Transfer, u64>,
+    // Lets a recipient page through only the transfers still waiting on them
+    pub recipient_status: MultiIndex<'a, (Addr, String), Transfer, u64>,
+}
+
+impl<'a> IndexList<Transfer> for TransferIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Transfer>> + '_> {
+        let v: Vec<&dyn Index<Transfer>> =
+            vec![&self.sender, &self.recipient, &self.status, &self.recipient_status];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn transfers<'a>() -> IndexedMap<'a, u64, Transfer, TransferIndexes<'a>> {
+    let indexes = TransferIndexes {
+        sender: MultiIndex::new(|_pk, t| t.sender.clone(), "transfers", "transfers__sender"),
+        recipient: MultiIndex::new(|_pk, t| t.recipient.clone(), "transfers", "transfers__recipient"),
+        status: MultiIndex::new(|_pk, t| t.status.as_str().to_string(), "transfers", "transfers__status"),
+        recipient_status: MultiIndex::new(
+            |_pk, t| (t.recipient.clone(), t.status.as_str().to_string()),
+            "transfers",
+            "transfers__recipient_status",
+        ),
+    };
+    IndexedMap::new("transfers", indexes)
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+pub enum StreamStatus {
+    Active,
+    Completed,
+    Cancelled,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+pub struct Stream {
+    pub id: u64,
+    pub sender: Addr,
+    pub recipient: Addr,
+    pub denom: String,
+    pub rate_per_second: Uint128,
+    pub start: u64,
+    pub end: u64,
+    pub deposit: Uint128,
+    pub withdrawn: Uint128,
+    pub status: StreamStatus,
+}
+
+impl Stream {
+    /// Total amount released to the recipient by time `now`, capped at the deposit.
+    pub fn vested(&self, now: u64) -> Uint128 {
+        let elapsed = now.clamp(self.start, self.end) - self.start;
+        self.rate_per_second * Uint128::from(elapsed)
+    }
+
+    pub fn withdrawable(&self, now: u64) -> Uint128 {
+        self.vested(now) - self.withdrawn
+    }
+}
+
+pub const NEXT_STREAM_ID: Item<u64> = Item::new("next_stream_id");
+
+pub struct StreamIndexes<'a> {
+    pub sender: MultiIndex<'a, Addr, Stream, u64>,
+    pub recipient: MultiIndex<'a, Addr, Stream, u64>,
+}
+
+impl<'a> IndexList<Stream> for StreamIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Stream>> + '_> {
+        let v: Vec<&dyn Index<Stream>> = vec![&self.sender, &self.recipient];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn streams<'a>() -> IndexedMap<'a, u64, Stream, StreamIndexes<'a>> {
+    let indexes = StreamIndexes {
+        sender: MultiIndex::new(|_pk, s| s.sender.clone(), "streams", "streams__sender"),
+        recipient: MultiIndex::new(|_pk, s| s.recipient.clone(), "streams", "streams__recipient"),
+    };
+    IndexedMap::new("streams", indexes)
+}
+/// secp256k1 public key each sender signs their vouchers with.
+pub const VOUCHER_KEYS: Map<&Addr, Binary> = Map::new("voucher_keys");
+/// Pre-funded voucher balances, keyed by (sender, denom).
+pub const VOUCHER_BALANCES: Map<(&Addr, &str), Uint128> = Map::new("voucher_balances");
+/// Nonces already redeemed, keyed by (sender, nonce).
+pub const USED_NONCES: Map<(&Addr, u64), Empty> = Map::new("used_nonces");
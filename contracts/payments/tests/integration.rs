@@ -1,21 +1,35 @@
-use cosmwasm_std::{coins, Addr, Coin, Timestamp, Uint128};
+use cosmwasm_std::{coins, to_json_binary, to_json_vec, Addr, Binary, Coin, Timestamp, Uint128};
 use cw_multi_test::{App, ContractWrapper, Executor};
+use cw20::{Cw20Coin, Cw20ExecuteMsg};
+use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+use sha2::{Digest, Sha256};
 
 use seimoney_payments::contract::{execute, instantiate, query};
-use seimoney_payments::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ConfigResp, TransferResp};
+use seimoney_payments::msg::{
+    ExecuteMsg, InstantiateMsg, QueryMsg, ConfigResp, FeeBalancesResp, ReceiveMsg, Recipient, StreamResp,
+    TransferItem, TransferResp, Voucher, VoucherAccountResp,
+};
+use seimoney_payments::state::{Asset, StreamStatus, TransferStatus};
 
 const ADMIN: &str = "admin";
 const USER1: &str = "user1";
 const USER2: &str = "user2";
 const DENOM: &str = "usei";
+const STABLE: &str = "uusdc";
 
 fn setup_contract(app: &mut App) -> Addr {
+    setup_contract_with_fee(app, None)
+}
+
+fn setup_contract_with_fee(app: &mut App, fee_bps: Option<u16>) -> Addr {
     let code = ContractWrapper::new(execute, instantiate, query);
     let code_id = app.store_code(Box::new(code));
 
     let msg = InstantiateMsg {
         admin: Some(ADMIN.to_string()),
         default_denom: DENOM.to_string(),
+        fee_bps,
+        alias_contract: None,
     };
 
     app.instantiate_contract(
@@ -59,10 +73,13 @@ fn test_create_transfer() {
     .unwrap();
 
     let msg = ExecuteMsg::CreateTransfer {
-        recipient: USER2.to_string(),
-        amount: Coin::new(100, DENOM),
+        recipient: Recipient::Address(USER2.to_string()),
+        amount: vec![Coin::new(100, DENOM)],
         remark: Some("Test transfer".to_string()),
         expiry_ts: None,
+        hashlock: None,
+        arbiter: None,
+        cancel_window_secs: None,
     };
 
     let res = app.execute_contract(
@@ -84,7 +101,7 @@ fn test_create_transfer() {
     assert_eq!(res.id, 1);
     assert_eq!(res.sender, USER1);
     assert_eq!(res.recipient, USER2);
-    assert_eq!(res.amount, Coin::new(100, DENOM));
+    assert_eq!(res.asset, Asset::Native(vec![Coin::new(100, DENOM)]));
     assert_eq!(res.remark, Some("Test transfer".to_string()));
     assert_eq!(res.status, "Open");
 }
@@ -105,10 +122,13 @@ fn test_claim_transfer() {
 
     // Create transfer
     let msg = ExecuteMsg::CreateTransfer {
-        recipient: USER2.to_string(),
-        amount: Coin::new(100, DENOM),
+        recipient: Recipient::Address(USER2.to_string()),
+        amount: vec![Coin::new(100, DENOM)],
         remark: None,
         expiry_ts: None,
+        hashlock: None,
+        arbiter: None,
+        cancel_window_secs: None,
     };
 
     app.execute_contract(
@@ -120,7 +140,7 @@ fn test_claim_transfer() {
     .unwrap();
 
     // Claim transfer
-    let claim_msg = ExecuteMsg::ClaimTransfer { id: 1 };
+    let claim_msg = ExecuteMsg::ClaimTransfer { id: 1, preimage: None, to: None };
     let res = app.execute_contract(
         Addr::unchecked(USER2),
         contract_addr.clone(),
@@ -167,10 +187,13 @@ fn test_refund_transfer() {
     });
     
     let msg = ExecuteMsg::CreateTransfer {
-        recipient: USER2.to_string(),
-        amount: Coin::new(100, DENOM),
+        recipient: Recipient::Address(USER2.to_string()),
+        amount: vec![Coin::new(100, DENOM)],
         remark: None,
         expiry_ts: Some(expiry),
+        hashlock: None,
+        arbiter: None,
+        cancel_window_secs: None,
     };
 
     app.execute_contract(
@@ -227,10 +250,13 @@ fn test_unauthorized_claim() {
 
     // Create transfer
     let msg = ExecuteMsg::CreateTransfer {
-        recipient: USER2.to_string(),
-        amount: Coin::new(100, DENOM),
+        recipient: Recipient::Address(USER2.to_string()),
+        amount: vec![Coin::new(100, DENOM)],
         remark: None,
         expiry_ts: None,
+        hashlock: None,
+        arbiter: None,
+        cancel_window_secs: None,
     };
 
     app.execute_contract(
@@ -242,7 +268,7 @@ fn test_unauthorized_claim() {
     .unwrap();
 
     // Try to claim with wrong user
-    let claim_msg = ExecuteMsg::ClaimTransfer { id: 1 };
+    let claim_msg = ExecuteMsg::ClaimTransfer { id: 1, preimage: None, to: None };
     let res = app.execute_contract(
         Addr::unchecked(USER1), // Wrong user (sender instead of recipient)
         contract_addr,
@@ -268,10 +294,13 @@ fn test_insufficient_funds() {
     .unwrap();
 
     let msg = ExecuteMsg::CreateTransfer {
-        recipient: USER2.to_string(),
-        amount: Coin::new(100, DENOM),
+        recipient: Recipient::Address(USER2.to_string()),
+        amount: vec![Coin::new(100, DENOM)],
         remark: None,
         expiry_ts: None,
+        hashlock: None,
+        arbiter: None,
+        cancel_window_secs: None,
     };
 
     let res = app.execute_contract(
@@ -282,4 +311,1308 @@ fn test_insufficient_funds() {
     );
 
     assert!(res.is_err());
-}
\ No newline at end of file
+}
+#[test]
+fn test_multi_denom_transfer() {
+    let mut app = App::default();
+    let contract_addr = setup_contract(&mut app);
+
+    // Give USER1 a stable plus some usei for gas top-up
+    app.sudo(cw_multi_test::SudoMsg::Bank(
+        cw_multi_test::BankSudo::Mint {
+            to_address: USER1.to_string(),
+            amount: vec![Coin::new(1000, STABLE), Coin::new(1000, DENOM)],
+        },
+    ))
+    .unwrap();
+
+    let msg = ExecuteMsg::CreateTransfer {
+        recipient: Recipient::Address(USER2.to_string()),
+        amount: vec![Coin::new(10, DENOM), Coin::new(500, STABLE)],
+        remark: Some("Payroll".to_string()),
+        expiry_ts: None,
+        hashlock: None,
+        arbiter: None,
+        cancel_window_secs: None,
+    };
+
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &msg,
+        &[Coin::new(500, STABLE), Coin::new(10, DENOM)],
+    )
+    .unwrap();
+
+    // Amounts are stored sorted by denom
+    let res: TransferResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetTransfer { id: 1 })
+        .unwrap();
+    assert_eq!(res.asset, Asset::Native(vec![Coin::new(10, DENOM), Coin::new(500, STABLE)]));
+
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr,
+        &ExecuteMsg::ClaimTransfer { id: 1, preimage: None, to: None },
+        &[],
+    )
+    .unwrap();
+
+    let balance = app.wrap().query_balance(USER2, STABLE).unwrap();
+    assert_eq!(balance.amount, Uint128::new(500));
+    let balance = app.wrap().query_balance(USER2, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(10));
+}
+
+#[test]
+fn test_multi_denom_funds_mismatch() {
+    let mut app = App::default();
+    let contract_addr = setup_contract(&mut app);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(
+        cw_multi_test::BankSudo::Mint {
+            to_address: USER1.to_string(),
+            amount: vec![Coin::new(1000, STABLE), Coin::new(1000, DENOM)],
+        },
+    ))
+    .unwrap();
+
+    // Extra coin attached that is not declared in amount
+    let msg = ExecuteMsg::CreateTransfer {
+        recipient: Recipient::Address(USER2.to_string()),
+        amount: vec![Coin::new(500, STABLE)],
+        remark: None,
+        expiry_ts: None,
+        hashlock: None,
+        arbiter: None,
+        cancel_window_secs: None,
+    };
+    let res = app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &msg,
+        &[Coin::new(500, STABLE), Coin::new(10, DENOM)],
+    );
+    assert!(res.is_err());
+
+    // Duplicate denoms in amount
+    let msg = ExecuteMsg::CreateTransfer {
+        recipient: Recipient::Address(USER2.to_string()),
+        amount: vec![Coin::new(250, STABLE), Coin::new(250, STABLE)],
+        remark: None,
+        expiry_ts: None,
+        hashlock: None,
+        arbiter: None,
+        cancel_window_secs: None,
+    };
+    let res = app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr,
+        &msg,
+        &coins(500, STABLE),
+    );
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_list_queries_use_indexes() {
+    let mut app = App::default();
+    let contract_addr = setup_contract(&mut app);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(
+        cw_multi_test::BankSudo::Mint {
+            to_address: USER1.to_string(),
+            amount: coins(1000, DENOM),
+        },
+    ))
+    .unwrap();
+
+    // Transfers 1, 3 and 4 go to USER2, transfer 2 goes to ADMIN
+    for recipient in [USER2, ADMIN, USER2, USER2] {
+        let msg = ExecuteMsg::CreateTransfer {
+            recipient: Recipient::Address(recipient.to_string()),
+            amount: vec![Coin::new(100, DENOM)],
+            remark: None,
+            expiry_ts: None,
+            hashlock: None,
+            arbiter: None,
+            cancel_window_secs: None,
+        };
+        app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &coins(100, DENOM))
+            .unwrap();
+    }
+
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &ExecuteMsg::ClaimTransfer { id: 3, preimage: None, to: None },
+        &[],
+    )
+    .unwrap();
+
+    let res: Vec<TransferResp> = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::ListBySender { sender: USER1.to_string(), start_after: Some(1), limit: Some(2) },
+        )
+        .unwrap();
+    assert_eq!(res.iter().map(|t| t.id).collect::<Vec<_>>(), vec![2, 3]);
+
+    let res: Vec<TransferResp> = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::ListByRecipient { recipient: USER2.to_string(), start_after: None, limit: None },
+        )
+        .unwrap();
+    assert_eq!(res.iter().map(|t| t.id).collect::<Vec<_>>(), vec![1, 3, 4]);
+
+    let res: Vec<TransferResp> = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::ListByStatus { status: TransferStatus::Claimed, start_after: None, limit: None },
+        )
+        .unwrap();
+    assert_eq!(res.iter().map(|t| t.id).collect::<Vec<_>>(), vec![3]);
+
+    let res: Vec<TransferResp> = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr,
+            &QueryMsg::ListOpenByRecipient { recipient: USER2.to_string(), start_after: Some(1), limit: None },
+        )
+        .unwrap();
+    assert_eq!(res.iter().map(|t| t.id).collect::<Vec<_>>(), vec![4]);
+}
+
+#[test]
+fn test_hashlocked_claim() {
+    let mut app = App::default();
+    let contract_addr = setup_contract(&mut app);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(
+        cw_multi_test::BankSudo::Mint {
+            to_address: USER1.to_string(),
+            amount: coins(1000, DENOM),
+        },
+    ))
+    .unwrap();
+
+    let secret = b"open sesame".to_vec();
+    let hashlock = Binary::from(Sha256::digest(&secret).to_vec());
+
+    let msg = ExecuteMsg::CreateTransfer {
+        recipient: Recipient::Address(USER2.to_string()),
+        amount: vec![Coin::new(100, DENOM)],
+        remark: None,
+        expiry_ts: None,
+        hashlock: Some(hashlock.clone()),
+        arbiter: None,
+        cancel_window_secs: None,
+    };
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &coins(100, DENOM))
+        .unwrap();
+
+    // Wrong preimage is rejected
+    let claim_msg = ExecuteMsg::ClaimTransfer {
+        id: 1,
+        preimage: Some(Binary::from(b"wrong".to_vec())),
+        to: None,
+    };
+    let res = app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &claim_msg, &[]);
+    assert!(res.is_err());
+
+    // Anyone with the preimage can claim, here to a third address
+    let claim_msg = ExecuteMsg::ClaimTransfer {
+        id: 1,
+        preimage: Some(Binary::from(secret.clone())),
+        to: Some("user3".to_string()),
+    };
+    let res = app
+        .execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &claim_msg, &[])
+        .unwrap();
+
+    let evt = res
+        .events
+        .iter()
+        .find(|e| e.ty == "wasm-seimoney.claim_transfer")
+        .unwrap();
+    let preimage = evt.attributes.iter().find(|a| a.key == "preimage").unwrap();
+    assert_eq!(preimage.value, Binary::from(secret).to_base64());
+
+    let balance = app.wrap().query_balance("user3", DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(100));
+
+    let res: TransferResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr, &QueryMsg::GetTransfer { id: 1 })
+        .unwrap();
+    assert_eq!(res.hashlock, Some(hashlock));
+    assert_eq!(res.status, "Claimed");
+}
+
+#[test]
+fn test_hashlocked_refund_after_expiry() {
+    let mut app = App::default();
+    let contract_addr = setup_contract(&mut app);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(
+        cw_multi_test::BankSudo::Mint {
+            to_address: USER1.to_string(),
+            amount: coins(1000, DENOM),
+        },
+    ))
+    .unwrap();
+
+    app.update_block(|block| {
+        block.time = Timestamp::from_seconds(1000);
+    });
+
+    let secret = b"swap secret".to_vec();
+    let msg = ExecuteMsg::CreateTransfer {
+        recipient: Recipient::Address(USER2.to_string()),
+        amount: vec![Coin::new(100, DENOM)],
+        remark: None,
+        expiry_ts: Some(2000),
+        hashlock: Some(Binary::from(Sha256::digest(&secret).to_vec())),
+        arbiter: None,
+        cancel_window_secs: None,
+    };
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &coins(100, DENOM))
+        .unwrap();
+
+    // The recipient cannot claim without the preimage
+    let claim_msg = ExecuteMsg::ClaimTransfer { id: 1, preimage: None, to: None };
+    let res = app.execute_contract(Addr::unchecked(USER2), contract_addr.clone(), &claim_msg, &[]);
+    assert!(res.is_err());
+
+    app.update_block(|block| {
+        block.time = Timestamp::from_seconds(2000);
+    });
+
+    // After expiry the preimage no longer unlocks the funds, but the sender can refund
+    let claim_msg = ExecuteMsg::ClaimTransfer { id: 1, preimage: Some(Binary::from(secret)), to: None };
+    let res = app.execute_contract(Addr::unchecked(USER2), contract_addr.clone(), &claim_msg, &[]);
+    assert!(res.is_err());
+
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr,
+        &ExecuteMsg::RefundTransfer { id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    let balance = app.wrap().query_balance(USER1, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(1000));
+}
+
+#[test]
+fn test_claim_fee_collection() {
+    let mut app = App::default();
+    let contract_addr = setup_contract_with_fee(&mut app, Some(100)); // 1%
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(
+        cw_multi_test::BankSudo::Mint {
+            to_address: USER1.to_string(),
+            amount: vec![Coin::new(1000, STABLE), Coin::new(1000, DENOM)],
+        },
+    ))
+    .unwrap();
+
+    let msg = ExecuteMsg::CreateTransfer {
+        recipient: Recipient::Address(USER2.to_string()),
+        amount: vec![Coin::new(50, DENOM), Coin::new(1000, STABLE)],
+        remark: None,
+        expiry_ts: None,
+        hashlock: None,
+        arbiter: None,
+        cancel_window_secs: None,
+    };
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &msg,
+        &[Coin::new(1000, STABLE), Coin::new(50, DENOM)],
+    )
+    .unwrap();
+
+    let res = app
+        .execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::ClaimTransfer { id: 1, preimage: None, to: None },
+            &[],
+        )
+        .unwrap();
+
+    let evt = res
+        .events
+        .iter()
+        .find(|e| e.ty == "wasm-seimoney.claim_transfer")
+        .unwrap();
+    let fee = evt.attributes.iter().find(|a| a.key == "fee").unwrap();
+    assert_eq!(fee.value, "0usei,10uusdc");
+
+    let balance = app.wrap().query_balance(USER2, STABLE).unwrap();
+    assert_eq!(balance.amount, Uint128::new(990));
+    let balance = app.wrap().query_balance(USER2, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(50));
+
+    let res: FeeBalancesResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::FeeBalances {})
+        .unwrap();
+    assert_eq!(res.fees, vec![Coin::new(10, STABLE)]);
+
+    // Only the admin can withdraw fees
+    let withdraw_msg = ExecuteMsg::WithdrawFees { to: "treasury".to_string() };
+    let res = app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &withdraw_msg, &[]);
+    assert!(res.is_err());
+
+    app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &withdraw_msg, &[])
+        .unwrap();
+
+    let balance = app.wrap().query_balance("treasury", STABLE).unwrap();
+    assert_eq!(balance.amount, Uint128::new(10));
+
+    let res: FeeBalancesResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr, &QueryMsg::FeeBalances {})
+        .unwrap();
+    assert!(res.fees.is_empty());
+}
+
+#[test]
+fn test_fee_bounds() {
+    let mut app = App::default();
+    let code = ContractWrapper::new(execute, instantiate, query);
+    let code_id = app.store_code(Box::new(code));
+
+    let msg = InstantiateMsg {
+        admin: Some(ADMIN.to_string()),
+        default_denom: DENOM.to_string(),
+        fee_bps: Some(1001),
+        alias_contract: None,
+    };
+    let res = app.instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "SeiMoney Payments", None);
+    assert!(res.is_err());
+
+    let contract_addr = setup_contract(&mut app);
+
+    let res = app.execute_contract(
+        Addr::unchecked(ADMIN),
+        contract_addr.clone(),
+        &ExecuteMsg::UpdateConfig { fee_bps: Some(2000), alias_contract: None },
+        &[],
+    );
+    assert!(res.is_err());
+
+    app.execute_contract(
+        Addr::unchecked(ADMIN),
+        contract_addr.clone(),
+        &ExecuteMsg::UpdateConfig { fee_bps: Some(250), alias_contract: None },
+        &[],
+    )
+    .unwrap();
+
+    let res: ConfigResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(res.fee_bps, 250);
+}
+
+#[test]
+fn test_stream_withdraw() {
+    let mut app = App::default();
+    let contract_addr = setup_contract(&mut app);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(
+        cw_multi_test::BankSudo::Mint {
+            to_address: USER1.to_string(),
+            amount: coins(1000, DENOM),
+        },
+    ))
+    .unwrap();
+
+    app.update_block(|block| {
+        block.time = Timestamp::from_seconds(1000);
+    });
+
+    // 10 usei per second for 100 seconds
+    let msg = ExecuteMsg::CreateStream {
+        recipient: USER2.to_string(),
+        rate_per_second: Uint128::new(10),
+        start: 1000,
+        end: 1100,
+    };
+
+    // Underfunded stream is rejected
+    let res = app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &coins(999, DENOM));
+    assert!(res.is_err());
+
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &coins(1000, DENOM))
+        .unwrap();
+
+    app.update_block(|block| {
+        block.time = Timestamp::from_seconds(1030);
+    });
+
+    let res: StreamResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetStream { id: 1 })
+        .unwrap();
+    assert_eq!(res.withdrawable, Uint128::new(300));
+
+    // Only the recipient can withdraw
+    let withdraw_msg = ExecuteMsg::WithdrawStream { id: 1 };
+    let res = app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &withdraw_msg, &[]);
+    assert!(res.is_err());
+
+    app.execute_contract(Addr::unchecked(USER2), contract_addr.clone(), &withdraw_msg, &[])
+        .unwrap();
+    let balance = app.wrap().query_balance(USER2, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(300));
+
+    // Nothing new has vested in the same block
+    let res = app.execute_contract(Addr::unchecked(USER2), contract_addr.clone(), &withdraw_msg, &[]);
+    assert!(res.is_err());
+
+    // Past the end only the remainder is paid out and the stream completes
+    app.update_block(|block| {
+        block.time = Timestamp::from_seconds(5000);
+    });
+    app.execute_contract(Addr::unchecked(USER2), contract_addr.clone(), &withdraw_msg, &[])
+        .unwrap();
+    let balance = app.wrap().query_balance(USER2, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(1000));
+
+    let res: Vec<StreamResp> = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr,
+            &QueryMsg::ListStreamsByRecipient { recipient: USER2.to_string(), start_after: None, limit: None },
+        )
+        .unwrap();
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].status, StreamStatus::Completed);
+    assert_eq!(res[0].withdrawn, Uint128::new(1000));
+}
+
+#[test]
+fn test_stream_cancel() {
+    let mut app = App::default();
+    let contract_addr = setup_contract(&mut app);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(
+        cw_multi_test::BankSudo::Mint {
+            to_address: USER1.to_string(),
+            amount: coins(1000, DENOM),
+        },
+    ))
+    .unwrap();
+
+    app.update_block(|block| {
+        block.time = Timestamp::from_seconds(1000);
+    });
+
+    let msg = ExecuteMsg::CreateStream {
+        recipient: USER2.to_string(),
+        rate_per_second: Uint128::new(10),
+        start: 1000,
+        end: 1100,
+    };
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &coins(1000, DENOM))
+        .unwrap();
+
+    app.update_block(|block| {
+        block.time = Timestamp::from_seconds(1010);
+    });
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &ExecuteMsg::WithdrawStream { id: 1 },
+        &[],
+    )
+    .unwrap();
+
+    app.update_block(|block| {
+        block.time = Timestamp::from_seconds(1040);
+    });
+
+    // Only the sender can cancel
+    let cancel_msg = ExecuteMsg::CancelStream { id: 1 };
+    let res = app.execute_contract(Addr::unchecked(USER2), contract_addr.clone(), &cancel_msg, &[]);
+    assert!(res.is_err());
+
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &cancel_msg, &[])
+        .unwrap();
+
+    // Recipient gets everything vested, sender the unvested remainder
+    let balance = app.wrap().query_balance(USER2, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(400));
+    let balance = app.wrap().query_balance(USER1, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(600));
+
+    let res: StreamResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetStream { id: 1 })
+        .unwrap();
+    assert_eq!(res.status, StreamStatus::Cancelled);
+
+    let res = app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr,
+        &ExecuteMsg::WithdrawStream { id: 1 },
+        &[],
+    );
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_batch_create_and_claim() {
+    let mut app = App::default();
+    let contract_addr = setup_contract(&mut app);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(
+        cw_multi_test::BankSudo::Mint {
+            to_address: USER1.to_string(),
+            amount: vec![Coin::new(1000, STABLE), Coin::new(1000, DENOM)],
+        },
+    ))
+    .unwrap();
+
+    let item = |recipient: &str, amount: Vec<Coin>| TransferItem {
+        recipient: Recipient::Address(recipient.to_string()),
+        amount,
+        remark: None,
+        expiry_ts: None,
+        hashlock: None,
+        arbiter: None,
+        cancel_window_secs: None,
+    };
+    let msg = ExecuteMsg::BatchCreateTransfer {
+        items: vec![
+            item(USER2, vec![Coin::new(100, DENOM)]),
+            item(USER2, vec![Coin::new(200, STABLE), Coin::new(50, DENOM)]),
+            item(ADMIN, vec![Coin::new(300, STABLE)]),
+        ],
+    };
+
+    // Funds must match the sum of all items
+    let res = app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &msg,
+        &[Coin::new(500, STABLE), Coin::new(100, DENOM)],
+    );
+    assert!(res.is_err());
+
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &msg,
+        &[Coin::new(500, STABLE), Coin::new(150, DENOM)],
+    )
+    .unwrap();
+
+    let res: Vec<TransferResp> = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::ListBySender { sender: USER1.to_string(), start_after: None, limit: None },
+        )
+        .unwrap();
+    assert_eq!(res.len(), 3);
+
+    // Transfer 3 belongs to ADMIN, so the whole batch fails and names it
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::BatchClaim { ids: vec![1, 3] },
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.root_cause().to_string().contains("Transfer 3"));
+    let res: TransferResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetTransfer { id: 1 })
+        .unwrap();
+    assert_eq!(res.status, "Open");
+
+    let res = app
+        .execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::BatchClaim { ids: vec![1, 2] },
+            &[],
+        )
+        .unwrap();
+
+    // One claim event per transfer, a single bank transfer to USER2
+    let claims = res.events.iter().filter(|e| e.ty == "wasm-seimoney.claim_transfer").count();
+    assert_eq!(claims, 2);
+    let transfers = res.events.iter().filter(|e| e.ty == "transfer").count();
+    assert_eq!(transfers, 1);
+
+    let balance = app.wrap().query_balance(USER2, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(150));
+    let balance = app.wrap().query_balance(USER2, STABLE).unwrap();
+    assert_eq!(balance.amount, Uint128::new(200));
+}
+
+#[test]
+fn test_batch_refund() {
+    let mut app = App::default();
+    let contract_addr = setup_contract(&mut app);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(
+        cw_multi_test::BankSudo::Mint {
+            to_address: USER1.to_string(),
+            amount: coins(1000, DENOM),
+        },
+    ))
+    .unwrap();
+
+    app.update_block(|block| {
+        block.time = Timestamp::from_seconds(1000);
+    });
+
+    let item = |expiry_ts: u64| TransferItem {
+        recipient: Recipient::Address(USER2.to_string()),
+        amount: vec![Coin::new(100, DENOM)],
+        remark: None,
+        expiry_ts: Some(expiry_ts),
+        hashlock: None,
+        arbiter: None,
+        cancel_window_secs: None,
+    };
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::BatchCreateTransfer { items: vec![item(1500), item(1500), item(3000)] },
+        &coins(300, DENOM),
+    )
+    .unwrap();
+
+    app.update_block(|block| {
+        block.time = Timestamp::from_seconds(2000);
+    });
+
+    // Transfer 3 has not expired yet
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::BatchRefund { ids: vec![1, 2, 3] },
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.root_cause().to_string().contains("Transfer 3: Not yet expired"));
+
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr,
+        &ExecuteMsg::BatchRefund { ids: vec![1, 2] },
+        &[],
+    )
+    .unwrap();
+
+    let balance = app.wrap().query_balance(USER1, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(900));
+}
+
+fn setup_alias_contract(app: &mut App) -> Addr {
+    let code = ContractWrapper::new(
+        seimoney_alias::contract::execute,
+        seimoney_alias::contract::instantiate,
+        seimoney_alias::contract::query,
+    );
+    let code_id = app.store_code(Box::new(code));
+
+    let msg = seimoney_alias::msg::InstantiateMsg {
+        admin: Some(ADMIN.to_string()),
+        min_username_length: None,
+        max_username_length: None,
+    };
+    app.instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "SeiMoney Alias", None)
+        .unwrap()
+}
+
+#[test]
+fn test_transfer_to_alias() {
+    let mut app = App::default();
+    let alias_addr = setup_alias_contract(&mut app);
+    let contract_addr = setup_contract(&mut app);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(
+        cw_multi_test::BankSudo::Mint {
+            to_address: USER1.to_string(),
+            amount: coins(1000, DENOM),
+        },
+    ))
+    .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        alias_addr.clone(),
+        &seimoney_alias::msg::ExecuteMsg::Register { username: "alice".to_string() },
+        &[],
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::CreateTransfer {
+        recipient: Recipient::Alias("alice".to_string()),
+        amount: vec![Coin::new(100, DENOM)],
+        remark: None,
+        expiry_ts: None,
+        hashlock: None,
+        arbiter: None,
+        cancel_window_secs: None,
+    };
+
+    // No alias contract configured yet
+    let res = app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &coins(100, DENOM));
+    assert!(res.is_err());
+
+    app.execute_contract(
+        Addr::unchecked(ADMIN),
+        contract_addr.clone(),
+        &ExecuteMsg::UpdateConfig { fee_bps: None, alias_contract: Some(alias_addr.to_string()) },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &coins(100, DENOM))
+        .unwrap();
+
+    let res: TransferResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetTransfer { id: 1 })
+        .unwrap();
+    assert_eq!(res.recipient, USER2);
+    assert_eq!(res.recipient_alias, Some("alice".to_string()));
+
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &ExecuteMsg::ClaimTransfer { id: 1, preimage: None, to: None },
+        &[],
+    )
+    .unwrap();
+    let balance = app.wrap().query_balance(USER2, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(100));
+
+    // Unknown usernames are rejected
+    let msg = ExecuteMsg::CreateTransfer {
+        recipient: Recipient::Alias("bob".to_string()),
+        amount: vec![Coin::new(100, DENOM)],
+        remark: None,
+        expiry_ts: None,
+        hashlock: None,
+        arbiter: None,
+        cancel_window_secs: None,
+    };
+    let err = app
+        .execute_contract(Addr::unchecked(USER1), contract_addr, &msg, &coins(100, DENOM))
+        .unwrap_err();
+    assert!(err.root_cause().to_string().contains("Alias not found: bob"));
+}
+
+fn setup_cw20(app: &mut App, holder: &str, amount: u128) -> Addr {
+    let code = ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    );
+    let code_id = app.store_code(Box::new(code));
+
+    let msg = cw20_base::msg::InstantiateMsg {
+        name: "Sei Dollar".to_string(),
+        symbol: "SUSD".to_string(),
+        decimals: 6,
+        initial_balances: vec![Cw20Coin { address: holder.to_string(), amount: Uint128::new(amount) }],
+        mint: None,
+        marketing: None,
+    };
+    app.instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "SUSD", None)
+        .unwrap()
+}
+
+fn cw20_balance(app: &App, token: &Addr, address: &str) -> Uint128 {
+    let res: cw20::BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(token, &cw20::Cw20QueryMsg::Balance { address: address.to_string() })
+        .unwrap();
+    res.balance
+}
+
+fn send_cw20_transfer(app: &mut App, token: &Addr, contract_addr: &Addr, amount: u128, expiry_ts: Option<u64>) {
+    let msg = Cw20ExecuteMsg::Send {
+        contract: contract_addr.to_string(),
+        amount: Uint128::new(amount),
+        msg: to_json_binary(&ReceiveMsg::CreateTransfer {
+            recipient: Recipient::Address(USER2.to_string()),
+            remark: None,
+            expiry_ts,
+            hashlock: None,
+            arbiter: None,
+            cancel_window_secs: None,
+        })
+        .unwrap(),
+    };
+    app.execute_contract(Addr::unchecked(USER1), token.clone(), &msg, &[])
+        .unwrap();
+}
+
+#[test]
+fn test_cw20_transfer_claim() {
+    let mut app = App::default();
+    let contract_addr = setup_contract_with_fee(&mut app, Some(100));
+    let token = setup_cw20(&mut app, USER1, 1000);
+
+    send_cw20_transfer(&mut app, &token, &contract_addr, 500, None);
+    assert_eq!(cw20_balance(&app, &token, contract_addr.as_str()), Uint128::new(500));
+
+    let res: TransferResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetTransfer { id: 1 })
+        .unwrap();
+    assert_eq!(res.sender, USER1);
+    assert_eq!(res.asset, Asset::Cw20 { contract: token.clone(), amount: Uint128::new(500) });
+
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &ExecuteMsg::ClaimTransfer { id: 1, preimage: None, to: None },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(cw20_balance(&app, &token, USER2), Uint128::new(495));
+
+    let res: FeeBalancesResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::FeeBalances {})
+        .unwrap();
+    assert!(res.fees.is_empty());
+    assert_eq!(res.cw20_fees, vec![Cw20Coin { address: token.to_string(), amount: Uint128::new(5) }]);
+
+    app.execute_contract(
+        Addr::unchecked(ADMIN),
+        contract_addr,
+        &ExecuteMsg::WithdrawFees { to: "treasury".to_string() },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(cw20_balance(&app, &token, "treasury"), Uint128::new(5));
+}
+
+#[test]
+fn test_cw20_transfer_refund() {
+    let mut app = App::default();
+    let contract_addr = setup_contract(&mut app);
+    let token = setup_cw20(&mut app, USER1, 1000);
+
+    app.update_block(|block| {
+        block.time = Timestamp::from_seconds(1000);
+    });
+    send_cw20_transfer(&mut app, &token, &contract_addr, 300, Some(2000));
+    send_cw20_transfer(&mut app, &token, &contract_addr, 200, Some(2000));
+    assert_eq!(cw20_balance(&app, &token, USER1), Uint128::new(500));
+
+    app.update_block(|block| {
+        block.time = Timestamp::from_seconds(2000);
+    });
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr,
+        &ExecuteMsg::BatchRefund { ids: vec![1, 2] },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(cw20_balance(&app, &token, USER1), Uint128::new(1000));
+}
+
+const ARBITER: &str = "arbiter";
+
+fn create_arbitrated_transfer(app: &mut App, contract_addr: &Addr, expiry_ts: Option<u64>) {
+    let msg = ExecuteMsg::CreateTransfer {
+        recipient: Recipient::Address(USER2.to_string()),
+        amount: vec![Coin::new(100, DENOM)],
+        remark: None,
+        expiry_ts,
+        hashlock: None,
+        arbiter: Some(ARBITER.to_string()),
+        cancel_window_secs: None,
+    };
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &coins(100, DENOM))
+        .unwrap();
+}
+
+#[test]
+fn test_arbiter_approve_and_reject() {
+    let mut app = App::default();
+    let contract_addr = setup_contract(&mut app);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(
+        cw_multi_test::BankSudo::Mint {
+            to_address: USER1.to_string(),
+            amount: coins(1000, DENOM),
+        },
+    ))
+    .unwrap();
+
+    create_arbitrated_transfer(&mut app, &contract_addr, None);
+    create_arbitrated_transfer(&mut app, &contract_addr, None);
+
+    // The recipient cannot claim an arbitrated transfer directly
+    let res = app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &ExecuteMsg::ClaimTransfer { id: 1, preimage: None, to: None },
+        &[],
+    );
+    assert!(res.is_err());
+
+    // Nor can anyone but the arbiter approve it
+    let res = app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &ExecuteMsg::ApproveTransfer { id: 1 },
+        &[],
+    );
+    assert!(res.is_err());
+
+    app.execute_contract(
+        Addr::unchecked(ARBITER),
+        contract_addr.clone(),
+        &ExecuteMsg::ApproveTransfer { id: 1 },
+        &[],
+    )
+    .unwrap();
+    let balance = app.wrap().query_balance(USER2, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(100));
+
+    app.execute_contract(
+        Addr::unchecked(ARBITER),
+        contract_addr.clone(),
+        &ExecuteMsg::RejectTransfer { id: 2 },
+        &[],
+    )
+    .unwrap();
+    let balance = app.wrap().query_balance(USER1, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(900));
+
+    let res: TransferResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr, &QueryMsg::GetTransfer { id: 2 })
+        .unwrap();
+    assert_eq!(res.arbiter, Some(ARBITER.to_string()));
+    assert_eq!(res.status, "Rejected");
+}
+
+#[test]
+fn test_arbiter_inactive_refund_after_expiry() {
+    let mut app = App::default();
+    let contract_addr = setup_contract(&mut app);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(
+        cw_multi_test::BankSudo::Mint {
+            to_address: USER1.to_string(),
+            amount: coins(1000, DENOM),
+        },
+    ))
+    .unwrap();
+
+    app.update_block(|block| {
+        block.time = Timestamp::from_seconds(1000);
+    });
+    create_arbitrated_transfer(&mut app, &contract_addr, Some(2000));
+
+    app.update_block(|block| {
+        block.time = Timestamp::from_seconds(2000);
+    });
+
+    // Too late for the arbiter, the sender gets the funds back
+    let res = app.execute_contract(
+        Addr::unchecked(ARBITER),
+        contract_addr.clone(),
+        &ExecuteMsg::ApproveTransfer { id: 1 },
+        &[],
+    );
+    assert!(res.is_err());
+
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr,
+        &ExecuteMsg::RefundTransfer { id: 1 },
+        &[],
+    )
+    .unwrap();
+    let balance = app.wrap().query_balance(USER1, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(1000));
+}
+
+fn create_cancellable_transfer(app: &mut App, contract_addr: &Addr, cancel_window_secs: u64) {
+    let msg = ExecuteMsg::CreateTransfer {
+        recipient: Recipient::Address(USER2.to_string()),
+        amount: vec![Coin::new(100, DENOM)],
+        remark: None,
+        expiry_ts: None,
+        hashlock: None,
+        arbiter: None,
+        cancel_window_secs: Some(cancel_window_secs),
+    };
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &coins(100, DENOM))
+        .unwrap();
+}
+
+#[test]
+fn test_cancel_window() {
+    let mut app = App::default();
+    let contract_addr = setup_contract(&mut app);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(
+        cw_multi_test::BankSudo::Mint {
+            to_address: USER1.to_string(),
+            amount: coins(1000, DENOM),
+        },
+    ))
+    .unwrap();
+
+    app.update_block(|block| {
+        block.time = Timestamp::from_seconds(1000);
+    });
+    create_cancellable_transfer(&mut app, &contract_addr, 60);
+    create_cancellable_transfer(&mut app, &contract_addr, 60);
+
+    let res: TransferResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetTransfer { id: 1 })
+        .unwrap();
+    assert_eq!(res.cancel_until, Some(1060));
+
+    // The recipient has to wait for the window to close
+    let claim_msg = ExecuteMsg::ClaimTransfer { id: 2, preimage: None, to: None };
+    let res = app.execute_contract(Addr::unchecked(USER2), contract_addr.clone(), &claim_msg, &[]);
+    assert!(res.is_err());
+
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::CancelTransfer { id: 1 },
+        &[],
+    )
+    .unwrap();
+    let balance = app.wrap().query_balance(USER1, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(900));
+
+    app.update_block(|block| {
+        block.time = Timestamp::from_seconds(1060);
+    });
+
+    // Window closed: the sender can no longer cancel and the recipient can claim
+    let res = app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::CancelTransfer { id: 2 },
+        &[],
+    );
+    assert!(res.is_err());
+
+    app.execute_contract(Addr::unchecked(USER2), contract_addr.clone(), &claim_msg, &[])
+        .unwrap();
+    let balance = app.wrap().query_balance(USER2, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(100));
+
+    let res: TransferResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr, &QueryMsg::GetTransfer { id: 1 })
+        .unwrap();
+    assert_eq!(res.status, "Cancelled");
+}
+
+#[test]
+fn test_refund_requires_expiry_and_recipient_reject() {
+    let mut app = App::default();
+    let contract_addr = setup_contract(&mut app);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(
+        cw_multi_test::BankSudo::Mint {
+            to_address: USER1.to_string(),
+            amount: coins(1000, DENOM),
+        },
+    ))
+    .unwrap();
+
+    let msg = ExecuteMsg::CreateTransfer {
+        recipient: Recipient::Address(USER2.to_string()),
+        amount: vec![Coin::new(100, DENOM)],
+        remark: None,
+        expiry_ts: None,
+        hashlock: None,
+        arbiter: None,
+        cancel_window_secs: None,
+    };
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &coins(100, DENOM))
+        .unwrap();
+
+    // Without an expiry the sender cannot pull the funds back
+    let res = app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::RefundTransfer { id: 1 },
+        &[],
+    );
+    assert!(res.is_err());
+
+    // Only the recipient (or an arbiter) may reject
+    let res = app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::RejectTransfer { id: 1 },
+        &[],
+    );
+    assert!(res.is_err());
+
+    app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &ExecuteMsg::RejectTransfer { id: 1 },
+        &[],
+    )
+    .unwrap();
+    let balance = app.wrap().query_balance(USER1, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(1000));
+
+    let res: TransferResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr, &QueryMsg::GetTransfer { id: 1 })
+        .unwrap();
+    assert_eq!(res.status, "Rejected");
+}
+
+fn sign_voucher(key: &SigningKey, voucher: &Voucher) -> Binary {
+    let hash = Sha256::digest(to_json_vec(voucher).unwrap());
+    let sig: Signature = key.sign_prehash(&hash).unwrap();
+    Binary::from(sig.to_bytes().as_slice())
+}
+
+#[test]
+fn test_redeem_voucher() {
+    let mut app = App::default();
+    let contract_addr = setup_contract(&mut app);
+
+    app.sudo(cw_multi_test::SudoMsg::Bank(
+        cw_multi_test::BankSudo::Mint {
+            to_address: USER1.to_string(),
+            amount: coins(1000, DENOM),
+        },
+    ))
+    .unwrap();
+
+    let key = SigningKey::from_slice(&[7u8; 32]).unwrap();
+    let pubkey = Binary::from(key.verifying_key().to_encoded_point(true).as_bytes());
+
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::SetVoucherKey { pubkey: pubkey.clone() },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::DepositVoucherFunds {},
+        &coins(500, DENOM),
+    )
+    .unwrap();
+
+    let voucher = Voucher {
+        chain_id: app.block_info().chain_id,
+        contract: contract_addr.to_string(),
+        sender: USER1.to_string(),
+        recipient: USER2.to_string(),
+        amount: Coin::new(200, DENOM),
+        nonce: 1,
+        expiry_ts: None,
+    };
+    let signature = sign_voucher(&key, &voucher);
+
+    // A tampered voucher does not verify
+    let tampered = Voucher { amount: Coin::new(400, DENOM), ..voucher.clone() };
+    let res = app.execute_contract(
+        Addr::unchecked(ADMIN),
+        contract_addr.clone(),
+        &ExecuteMsg::RedeemVoucher { voucher: tampered, signature: signature.clone() },
+        &[],
+    );
+    assert!(res.is_err());
+
+    // A voucher signed for another deployment is rejected
+    let foreign = Voucher { contract: "sei1othercontract".to_string(), ..voucher.clone() };
+    let signature_foreign = sign_voucher(&key, &foreign);
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::RedeemVoucher { voucher: foreign, signature: signature_foreign },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Voucher was issued for another chain or contract");
+
+    // Anyone can submit a valid voucher, funds go to the recipient
+    let redeem = ExecuteMsg::RedeemVoucher { voucher: voucher.clone(), signature };
+    app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &redeem, &[])
+        .unwrap();
+    let balance = app.wrap().query_balance(USER2, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(200));
+
+    // Replaying the same nonce fails
+    let res = app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &redeem, &[]);
+    assert!(res.is_err());
+
+    // Cannot draw more than the remaining balance
+    let big = Voucher { amount: Coin::new(400, DENOM), nonce: 2, ..voucher };
+    let signature = sign_voucher(&key, &big);
+    let res = app.execute_contract(
+        Addr::unchecked(USER2),
+        contract_addr.clone(),
+        &ExecuteMsg::RedeemVoucher { voucher: big, signature },
+        &[],
+    );
+    assert!(res.is_err());
+
+    let account: VoucherAccountResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::VoucherAccount { sender: USER1.to_string() })
+        .unwrap();
+    assert_eq!(account.pubkey, Some(pubkey));
+    assert_eq!(account.balance, vec![Coin::new(300, DENOM)]);
+
+    let nonces: Vec<u64> = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::UsedNonces { sender: USER1.to_string(), start_after: None, limit: None },
+        )
+        .unwrap();
+    assert_eq!(nonces, vec![1]);
+
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::WithdrawVoucherFunds { amount: vec![Coin::new(300, DENOM)] },
+        &[],
+    )
+    .unwrap();
+    let balance = app.wrap().query_balance(USER1, DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(800));
+}
@@ -2,11 +2,12 @@
 use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
-    ensure, to_json_binary, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Order,
-    Response, StdResult, Uint128,
+    ensure, to_json_binary, Addr, Api, BankMsg, Binary, Coin, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
 use cw_storage_plus::Bound;
+use seimoney_common::math::{calculate_fee, BASIS_POINTS};
 
 use crate::error::ContractError;
 use crate::error::ContractError::*;
@@ -17,7 +18,10 @@ use crate::state::*;
 const CONTRACT_NAME: &str = "crates.io:seimoney-pots";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-#[entry_point]
+const VAULT_DEPOSIT_REPLY_ID: u64 = 1;
+const VAULT_WITHDRAW_REPLY_ID: u64 = 2;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
@@ -28,9 +32,19 @@ pub fn instantiate(
         Some(a) => deps.api.addr_validate(&a)?,
         None => info.sender.clone(),
     };
+    let penalty_recipient = match msg.penalty_recipient {
+        Some(r) => validate_penalty_recipient(deps.api, r)?,
+        None => PenaltyRecipient::Savers,
+    };
+    let vault_contract = msg
+        .vault_contract
+        .map(|a| deps.api.addr_validate(&a))
+        .transpose()?;
     let cfg = Config {
         admin,
         default_denom: msg.default_denom,
+        penalty_recipient,
+        vault_contract,
     };
     CONFIG.save(deps.storage, &cfg)?;
     NEXT_POT_ID.save(deps.storage, &1u64)?;
@@ -38,7 +52,7 @@ pub fn instantiate(
     Ok(Response::new().add_attribute("action", "instantiate"))
 }
 
-#[entry_point]
+#[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
     _env: Env,
@@ -46,28 +60,95 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::OpenPot { goal, label } => exec_open_pot(deps, _env, info, goal, label),
+        ExecuteMsg::OpenPot {
+            goal,
+            label,
+            unlock_at,
+            early_break_penalty_bps,
+            yield_vault_id,
+        } => exec_open_pot(
+            deps,
+            _env,
+            info,
+            goal,
+            PotTerms {
+                label,
+                unlock_at,
+                early_break_penalty_bps,
+                yield_vault_id,
+            },
+        ),
         ExecuteMsg::DepositPot { pot_id, amount } => {
             exec_deposit_pot(deps, _env, info, pot_id, amount)
         }
         ExecuteMsg::BreakPot { pot_id } => exec_break_pot(deps, _env, info, pot_id),
         ExecuteMsg::ClosePot { pot_id } => exec_close_pot(deps, _env, info, pot_id),
+        ExecuteMsg::UpdateConfig {
+            penalty_recipient,
+            vault_contract,
+        } => exec_update_config(deps, info, penalty_recipient, vault_contract),
+        ExecuteMsg::DepositAutoSaveFunds {} => exec_deposit_auto_save_funds(deps, info),
+        ExecuteMsg::WithdrawAutoSaveFunds { amount } => {
+            exec_withdraw_auto_save_funds(deps, info, amount)
+        }
+        ExecuteMsg::SetAutoSave {
+            pot_id,
+            amount,
+            interval_secs,
+        } => exec_set_auto_save(deps, _env, info, pot_id, amount, interval_secs),
+        ExecuteMsg::CancelAutoSave { pot_id } => exec_cancel_auto_save(deps, info, pot_id),
+        ExecuteMsg::ProcessAutoSaves { limit } => exec_process_auto_saves(deps, _env, limit),
     }
 }
 
+/// Optional settings of a new pot.
+struct PotTerms {
+    label: Option<String>,
+    unlock_at: Option<u64>,
+    early_break_penalty_bps: Option<u16>,
+    yield_vault_id: Option<u64>,
+}
+
 fn exec_open_pot(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     goal: Coin,
-    label: Option<String>,
+    terms: PotTerms,
 ) -> Result<Response, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
+    let PotTerms {
+        label,
+        unlock_at,
+        early_break_penalty_bps,
+        yield_vault_id,
+    } = terms;
 
     // Validate denom
     ensure!(goal.denom == cfg.default_denom, InvalidDenom);
     ensure!(goal.amount > Uint128::zero(), InvalidGoalAmount);
 
+    // Validate time lock
+    if let Some(unlock_at) = unlock_at {
+        ensure!(unlock_at > _env.block.time.seconds(), InvalidUnlockTime);
+    }
+    if let Some(bps) = early_break_penalty_bps {
+        ensure!(
+            unlock_at.is_some() && bps > 0 && bps <= BASIS_POINTS,
+            InvalidPenalty
+        );
+    }
+
+    // The vault must exist and hold the pot's denom
+    if let Some(vault_id) = yield_vault_id {
+        let vault_contract = cfg.vault_contract.as_ref().ok_or(VaultNotConfigured)?;
+        let vault: VaultInfo = deps
+            .querier
+            .query_wasm_smart(vault_contract, &VaultQueryMsg::GetVault { id: vault_id })
+            .map_err(|_| VaultNotFound)?;
+        ensure!(vault.denom == goal.denom, InvalidDenom);
+    }
+
     let mut id = NEXT_POT_ID.load(deps.storage)?;
     let pot = Pot {
         id,
@@ -78,6 +159,12 @@ fn exec_open_pot(
         created_at: _env.block.time.seconds(),
         closed: false,
         broken: false,
+        unlock_at,
+        early_break_penalty_bps,
+        yield_vault_id,
+        vault_shares: Uint128::zero(),
+        penalty_index: Decimal::zero(),
+        accrued_bonus: Uint128::zero(),
     };
 
     POTS.save(deps.storage, id, &pot)?;
@@ -105,7 +192,7 @@ fn exec_open_pot(
 fn exec_deposit_pot(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     pot_id: u64,
     amount: Coin,
 ) -> Result<Response, ContractError> {
@@ -120,22 +207,157 @@ fn exec_deposit_pot(
     ensure!(amount.denom == cfg.default_denom, InvalidDenom);
     ensure!(amount.amount > Uint128::zero(), InvalidDepositAmount);
 
-    // Update pot
-    pot.current += amount.amount;
-    POTS.save(deps.storage, pot_id, &pot)?;
+    // Deposits must be backed by exactly the declared funds
+    ensure!(info.funds == [amount.clone()], FundsMismatch);
+
+    // Friends and family may top up someone else's pot
+    credit_pot(
+        deps.storage,
+        &mut pot,
+        &info.sender,
+        amount.amount,
+        _env.block.time.seconds(),
+    )?;
+
+    let mut res = Response::new();
+    if let Some(msg) = forward_to_vault(deps.storage, &pot, amount.clone())? {
+        res = res.add_submessage(msg);
+    }
 
-    Ok(Response::new()
+    Ok(res
         .add_event(evt_deposit_pot(
             pot_id,
             pot.owner.as_str(),
+            info.sender.as_str(),
             &amount.amount,
             &amount.denom,
         ))
         .add_attribute("action", "deposit_pot"))
 }
 
-fn exec_break_pot(
+/// Adds `amount` to an open pot on behalf of `depositor`, keeping the depositor
+/// ledger and the locked savings total in step.
+fn credit_pot(
+    storage: &mut dyn Storage,
+    pot: &mut Pot,
+    depositor: &Addr,
+    amount: Uint128,
+    now: u64,
+) -> StdResult<()> {
+    // Time-locked savings earn a share of penalties forfeited after they were deposited
+    if pot.unlock_at.is_some() {
+        checkpoint_bonus(storage, pot)?;
+        let locked = LOCKED_SAVINGS.may_load(storage)?.unwrap_or_default();
+        LOCKED_SAVINGS.save(storage, &(locked + amount))?;
+    }
+    pot.current += amount;
+    POTS.save(storage, pot.id, pot)?;
+
+    let key = (pot.id, depositor.clone());
+    let mut deposit = DEPOSITS.may_load(storage, key.clone())?.unwrap_or(Deposit {
+        amount: Uint128::zero(),
+        deposit_count: 0,
+        last_deposit_at: 0,
+    });
+    deposit.amount += amount;
+    deposit.deposit_count += 1;
+    deposit.last_deposit_at = now;
+    DEPOSITS.save(storage, key, &deposit)
+}
+
+/// Forwards a deposit of a vault-backed pot to its vault; the minted shares are
+/// credited to the pot in `reply`.
+fn forward_to_vault(
+    storage: &mut dyn Storage,
+    pot: &Pot,
+    amount: Coin,
+) -> Result<Option<SubMsg>, ContractError> {
+    let vault_id = match pot.yield_vault_id {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+    let vault_contract = CONFIG
+        .load(storage)?
+        .vault_contract
+        .ok_or(VaultNotConfigured)?;
+    PENDING_VAULT_DEPOSITS.push_back(storage, &(pot.id, amount.amount))?;
+
+    Ok(Some(SubMsg::reply_on_success(
+        WasmMsg::Execute {
+            contract_addr: vault_contract.to_string(),
+            msg: to_json_binary(&VaultExecuteMsg::Deposit {
+                vault_id,
+                amount: amount.clone(),
+            })?,
+            funds: vec![amount],
+        },
+        VAULT_DEPOSIT_REPLY_ID,
+    )))
+}
+
+/// Pays a closed or broken pot out to its owner, less a `penalty_bps` penalty plus
+/// `bonus`. Vault-backed pots redeem their shares first and are paid out in `reply`,
+/// where the penalty is charged on what the vault actually returned.
+fn pay_out(
     deps: DepsMut,
+    env: &Env,
+    pot: &Pot,
+    penalty_bps: Option<u16>,
+    bonus: Uint128,
+) -> Result<Response, ContractError> {
+    if pot.vault_shares.is_zero() {
+        let penalty = penalty_bps.map_or(Uint128::zero(), |bps| calculate_fee(pot.current, bps));
+        let payout = pot.current - penalty + bonus;
+        let mut res = Response::new();
+        if !payout.is_zero() {
+            res = res.add_message(BankMsg::Send {
+                to_address: pot.owner.to_string(),
+                amount: vec![Coin::new(payout.u128(), pot.goal.denom.clone())],
+            });
+        }
+        return take_penalty(deps.storage, pot, penalty, res);
+    }
+
+    let vault_id = pot.yield_vault_id.ok_or(VaultNotConfigured)?;
+    let vault_contract = CONFIG
+        .load(deps.storage)?
+        .vault_contract
+        .ok_or(VaultNotConfigured)?;
+    let balance_before = deps
+        .querier
+        .query_balance(&env.contract.address, &pot.goal.denom)?
+        .amount;
+    PENDING_WITHDRAWAL.save(
+        deps.storage,
+        &PendingWithdrawal {
+            pot_id: pot.id,
+            balance_before,
+            penalty_bps,
+            bonus,
+        },
+    )?;
+    let held = VAULT_SHARES_HELD.load(deps.storage, vault_id)?;
+    VAULT_SHARES_HELD.save(
+        deps.storage,
+        vault_id,
+        &held.checked_sub(pot.vault_shares).map_err(StdError::from)?,
+    )?;
+
+    Ok(Response::new().add_submessage(SubMsg::reply_on_success(
+        WasmMsg::Execute {
+            contract_addr: vault_contract.to_string(),
+            msg: to_json_binary(&VaultExecuteMsg::Withdraw {
+                vault_id,
+                shares: pot.vault_shares.to_string(),
+            })?,
+            funds: vec![],
+        },
+        VAULT_WITHDRAW_REPLY_ID,
+    )))
+}
+
+fn exec_break_pot(
+    mut deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     pot_id: u64,
@@ -152,29 +374,40 @@ fn exec_break_pot(
     // Check if pot has funds
     ensure!(pot.current > Uint128::zero(), PotEmpty);
 
+    // Locked pots can only be broken early if they carry a penalty
+    let locked = pot.unlock_at.is_some_and(|t| _env.block.time.seconds() < t);
+    let penalty_bps = match (locked, pot.early_break_penalty_bps) {
+        (false, _) => None,
+        (true, Some(bps)) => Some(bps),
+        (true, None) => return Err(PotLocked),
+    };
+
     // Mark as broken
     pot.broken = true;
     POTS.save(deps.storage, pot_id, &pot)?;
+    clear_auto_save(deps.storage, pot_id)?;
+
+    let denom = pot.goal.denom.clone();
+    let bonus = release_locked_savings(deps.storage, &pot, locked)?;
 
     // Send funds back to owner
-    let bank = BankMsg::Send {
-        to_address: pot.owner.to_string(),
-        amount: vec![Coin::new(pot.current.u128(), pot.goal.denom.clone())],
-    };
+    let mut res = pay_out(deps.branch(), &_env, &pot, penalty_bps, bonus)?;
+    if !bonus.is_zero() {
+        res = res.add_event(evt_saver_bonus(pot_id, pot.owner.as_str(), &bonus, &denom));
+    }
 
-    Ok(Response::new()
-        .add_message(bank)
+    Ok(res
         .add_event(evt_break_pot(
             pot_id,
             pot.owner.as_str(),
             &pot.current,
-            &pot.goal.denom,
+            &denom,
         ))
         .add_attribute("action", "break_pot"))
 }
 
 fn exec_close_pot(
-    deps: DepsMut,
+    mut deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     pot_id: u64,
@@ -191,18 +424,29 @@ fn exec_close_pot(
     // Check if goal reached
     ensure!(pot.current >= pot.goal.amount, GoalNotReached);
 
+    // Reaching the goal does not lift the lock, and only locked savers share the penalty pool
+    let locked = pot.unlock_at.is_some_and(|t| _env.block.time.seconds() < t);
+    ensure!(!locked, PotLocked);
+
     // Mark as closed
     pot.closed = true;
     POTS.save(deps.storage, pot_id, &pot)?;
+    clear_auto_save(deps.storage, pot_id)?;
+
+    let bonus = release_locked_savings(deps.storage, &pot, false)?;
 
     // Send funds to owner
-    let bank = BankMsg::Send {
-        to_address: pot.owner.to_string(),
-        amount: vec![Coin::new(pot.current.u128(), pot.goal.denom.clone())],
-    };
+    let mut res = pay_out(deps.branch(), &_env, &pot, None, bonus)?;
+    if !bonus.is_zero() {
+        res = res.add_event(evt_saver_bonus(
+            pot_id,
+            pot.owner.as_str(),
+            &bonus,
+            &pot.goal.denom,
+        ));
+    }
 
-    Ok(Response::new()
-        .add_message(bank)
+    Ok(res
         .add_event(evt_close_pot(
             pot_id,
             pot.owner.as_str(),
@@ -212,7 +456,403 @@ fn exec_close_pot(
         .add_attribute("action", "close_pot"))
 }
 
-#[entry_point]
+fn exec_deposit_auto_save_funds(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let coin = match info.funds.as_slice() {
+        [coin] => coin,
+        _ => return Err(FundsMismatch),
+    };
+    ensure!(coin.denom == cfg.default_denom, InvalidDenom);
+    ensure!(!coin.amount.is_zero(), InvalidDepositAmount);
+
+    let balance = AUTO_SAVE_BALANCES
+        .may_load(deps.storage, info.sender.clone())?
+        .unwrap_or_default()
+        + coin.amount;
+    AUTO_SAVE_BALANCES.save(deps.storage, info.sender.clone(), &balance)?;
+
+    Ok(Response::new()
+        .add_event(evt_auto_save_funds(
+            info.sender.as_str(),
+            &coin.amount,
+            &coin.denom,
+            &balance,
+        ))
+        .add_attribute("action", "deposit_auto_save_funds"))
+}
+
+fn exec_withdraw_auto_save_funds(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    ensure!(!amount.is_zero(), InvalidDepositAmount);
+
+    let balance = AUTO_SAVE_BALANCES
+        .may_load(deps.storage, info.sender.clone())?
+        .unwrap_or_default();
+    ensure!(balance >= amount, InsufficientAutoSaveBalance);
+    let balance = balance - amount;
+    AUTO_SAVE_BALANCES.save(deps.storage, info.sender.clone(), &balance)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin::new(amount.u128(), cfg.default_denom.clone())],
+        })
+        .add_event(evt_auto_save_funds(
+            info.sender.as_str(),
+            &amount,
+            &cfg.default_denom,
+            &balance,
+        ))
+        .add_attribute("action", "withdraw_auto_save_funds"))
+}
+
+fn exec_set_auto_save(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pot_id: u64,
+    amount: Uint128,
+    interval_secs: u64,
+) -> Result<Response, ContractError> {
+    let pot = POTS.load(deps.storage, pot_id).map_err(|_| PotNotFound)?;
+
+    // Only owner can schedule auto-saves
+    ensure!(info.sender == pot.owner, Unauthorized);
+    ensure!(!pot.closed, PotAlreadyClosed);
+    ensure!(!pot.broken, PotAlreadyBroken);
+    ensure!(!amount.is_zero() && interval_secs > 0, InvalidAutoSave);
+
+    clear_auto_save(deps.storage, pot_id)?;
+    let next_due_ts = env.block.time.seconds() + interval_secs;
+    AUTO_SAVES.save(
+        deps.storage,
+        pot_id,
+        &AutoSave {
+            amount,
+            interval_secs,
+            next_due_ts,
+        },
+    )?;
+    AUTO_SAVE_QUEUE.save(deps.storage, (next_due_ts, pot_id), &Empty {})?;
+
+    Ok(Response::new()
+        .add_event(evt_set_auto_save(
+            pot_id,
+            pot.owner.as_str(),
+            &amount,
+            interval_secs,
+            next_due_ts,
+        ))
+        .add_attribute("action", "set_auto_save"))
+}
+
+fn exec_cancel_auto_save(
+    deps: DepsMut,
+    info: MessageInfo,
+    pot_id: u64,
+) -> Result<Response, ContractError> {
+    let pot = POTS.load(deps.storage, pot_id).map_err(|_| PotNotFound)?;
+    ensure!(info.sender == pot.owner, Unauthorized);
+    ensure!(AUTO_SAVES.has(deps.storage, pot_id), AutoSaveNotFound);
+
+    clear_auto_save(deps.storage, pot_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_auto_save")
+        .add_attribute("pot_id", pot_id.to_string()))
+}
+
+fn exec_process_auto_saves(
+    deps: DepsMut,
+    env: Env,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(10).min(30) as usize;
+    let now = env.block.time.seconds();
+    let denom = CONFIG.load(deps.storage)?.default_denom;
+
+    // Everything due up to and including `now`
+    let due: Vec<(u64, u64)> = AUTO_SAVE_QUEUE
+        .keys(
+            deps.storage,
+            None,
+            Some(Bound::exclusive((now + 1, 0))),
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+
+    let mut res = Response::new();
+    let mut executed = 0u32;
+    for (due_ts, pot_id) in due {
+        AUTO_SAVE_QUEUE.remove(deps.storage, (due_ts, pot_id));
+        let mut schedule = AUTO_SAVES.load(deps.storage, pot_id)?;
+        let mut pot = POTS.load(deps.storage, pot_id)?;
+        let owner = pot.owner.clone();
+
+        // Missed periods are not back-filled; move to the next slot after `now`
+        let periods = (now - due_ts) / schedule.interval_secs + 1;
+        schedule.next_due_ts = due_ts + periods * schedule.interval_secs;
+
+        let balance = AUTO_SAVE_BALANCES
+            .may_load(deps.storage, owner.clone())?
+            .unwrap_or_default();
+        if balance < schedule.amount {
+            res = res.add_event(evt_auto_save_skipped(
+                pot_id,
+                owner.as_str(),
+                &schedule.amount,
+                schedule.next_due_ts,
+            ));
+        } else {
+            AUTO_SAVE_BALANCES.save(deps.storage, owner.clone(), &(balance - schedule.amount))?;
+            credit_pot(deps.storage, &mut pot, &owner, schedule.amount, now)?;
+            let amount = Coin::new(schedule.amount.u128(), denom.clone());
+            if let Some(msg) = forward_to_vault(deps.storage, &pot, amount)? {
+                res = res.add_submessage(msg);
+            }
+            executed += 1;
+            res = res.add_event(evt_auto_save(
+                pot_id,
+                owner.as_str(),
+                &schedule.amount,
+                &denom,
+                schedule.next_due_ts,
+            ));
+        }
+
+        AUTO_SAVES.save(deps.storage, pot_id, &schedule)?;
+        AUTO_SAVE_QUEUE.save(deps.storage, (schedule.next_due_ts, pot_id), &Empty {})?;
+    }
+
+    Ok(res
+        .add_attribute("action", "process_auto_saves")
+        .add_attribute("executed", executed.to_string()))
+}
+
+fn clear_auto_save(storage: &mut dyn Storage, pot_id: u64) -> StdResult<()> {
+    if let Some(schedule) = AUTO_SAVES.may_load(storage, pot_id)? {
+        AUTO_SAVE_QUEUE.remove(storage, (schedule.next_due_ts, pot_id));
+        AUTO_SAVES.remove(storage, pot_id);
+    }
+    Ok(())
+}
+
+fn exec_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    penalty_recipient: Option<PenaltyRecipientMsg>,
+    vault_contract: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+    ensure!(info.sender == cfg.admin, Unauthorized);
+
+    if let Some(r) = penalty_recipient {
+        cfg.penalty_recipient = validate_penalty_recipient(deps.api, r)?;
+    }
+    if let Some(vault_contract) = vault_contract {
+        let vault_contract = deps.api.addr_validate(&vault_contract)?;
+        if cfg.vault_contract.as_ref() != Some(&vault_contract) {
+            // Shares can only be redeemed from the vault contract that minted them
+            let outstanding = VAULT_SHARES_HELD
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?
+                .iter()
+                .any(|(_, shares)| !shares.is_zero());
+            ensure!(!outstanding, VaultSharesOutstanding);
+        }
+        cfg.vault_contract = Some(vault_contract);
+    }
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+fn validate_penalty_recipient(
+    api: &dyn Api,
+    msg: PenaltyRecipientMsg,
+) -> StdResult<PenaltyRecipient> {
+    Ok(match msg {
+        PenaltyRecipientMsg::Treasury(addr) => {
+            PenaltyRecipient::Treasury(api.addr_validate(&addr)?)
+        }
+        PenaltyRecipientMsg::Savers {} => PenaltyRecipient::Savers,
+    })
+}
+
+/// Sends an early-break penalty to the treasury, or adds it to the savers' pool.
+fn take_penalty(
+    storage: &mut dyn Storage,
+    pot: &Pot,
+    penalty: Uint128,
+    mut res: Response,
+) -> Result<Response, ContractError> {
+    if penalty.is_zero() {
+        return Ok(res);
+    }
+    let denom = &pot.goal.denom;
+    let recipient = match CONFIG.load(storage)?.penalty_recipient {
+        PenaltyRecipient::Treasury(treasury) => {
+            res = res.add_message(BankMsg::Send {
+                to_address: treasury.to_string(),
+                amount: vec![Coin::new(penalty.u128(), denom.clone())],
+            });
+            treasury.to_string()
+        }
+        PenaltyRecipient::Savers => {
+            let pool = PENALTY_POOL.may_load(storage)?.unwrap_or_default();
+            PENALTY_POOL.save(storage, &(pool + penalty))?;
+            share_penalty(storage, penalty)?;
+            "savers".to_string()
+        }
+    };
+    Ok(res.add_event(evt_early_break_penalty(
+        pot.id,
+        pot.owner.as_str(),
+        &penalty,
+        denom,
+        &recipient,
+    )))
+}
+
+/// Settles a time-locked pot's share of the penalties shared out since its last
+/// checkpoint. Must run before the pot's balance changes.
+fn checkpoint_bonus(storage: &dyn Storage, pot: &mut Pot) -> StdResult<()> {
+    let index = PENALTY_INDEX.may_load(storage)?.unwrap_or_default();
+    pot.accrued_bonus += pot.current * (index - pot.penalty_index);
+    pot.penalty_index = index;
+    Ok(())
+}
+
+/// Shares `amount` of the penalty pool out across the savings locked right now.
+fn share_penalty(storage: &mut dyn Storage, amount: Uint128) -> StdResult<()> {
+    let amount = amount + UNALLOCATED_PENALTIES.may_load(storage)?.unwrap_or_default();
+    let locked = LOCKED_SAVINGS.may_load(storage)?.unwrap_or_default();
+    if locked.is_zero() {
+        return UNALLOCATED_PENALTIES.save(storage, &amount);
+    }
+    let index = PENALTY_INDEX.may_load(storage)?.unwrap_or_default();
+    PENALTY_INDEX.save(storage, &(index + Decimal::from_ratio(amount, locked)))?;
+    UNALLOCATED_PENALTIES.save(storage, &Uint128::zero())
+}
+
+/// Takes a paid-out time-locked pot out of the locked savings total and, unless it
+/// was broken before `unlock_at`, returns its share of the penalties that came in
+/// while it was locked. A penalized pot's share goes to the remaining savers.
+fn release_locked_savings(
+    storage: &mut dyn Storage,
+    pot: &Pot,
+    penalized: bool,
+) -> StdResult<Uint128> {
+    if pot.unlock_at.is_none() {
+        return Ok(Uint128::zero());
+    }
+    let mut pot = pot.clone();
+    checkpoint_bonus(storage, &mut pot)?;
+    let locked = LOCKED_SAVINGS.may_load(storage)?.unwrap_or_default();
+    LOCKED_SAVINGS.save(storage, &locked.checked_sub(pot.current)?)?;
+    if penalized {
+        if !pot.accrued_bonus.is_zero() {
+            share_penalty(storage, pot.accrued_bonus)?;
+        }
+        return Ok(Uint128::zero());
+    }
+
+    let pool = PENALTY_POOL.may_load(storage)?.unwrap_or_default();
+    PENALTY_POOL.save(storage, &(pool - pot.accrued_bonus))?;
+    Ok(pot.accrued_bonus)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        VAULT_DEPOSIT_REPLY_ID => reply_vault_deposit(deps, env),
+        VAULT_WITHDRAW_REPLY_ID => reply_vault_withdraw(deps, env),
+        id => Err(UnknownReplyId { id }),
+    }
+}
+
+/// Credits the shares minted by a forwarded deposit to its pot.
+fn reply_vault_deposit(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let (pot_id, amount) = PENDING_VAULT_DEPOSITS
+        .pop_front(deps.storage)?
+        .ok_or_else(|| StdError::not_found("pending vault deposit"))?;
+    let mut pot = POTS.load(deps.storage, pot_id)?;
+    let vault_id = pot.yield_vault_id.ok_or(VaultNotConfigured)?;
+    let vault_contract = CONFIG
+        .load(deps.storage)?
+        .vault_contract
+        .ok_or(VaultNotConfigured)?;
+
+    // The vault does not report minted shares, so diff our position against
+    // the shares already attributed to pots
+    let position: VaultPositionResp = deps.querier.query_wasm_smart(
+        vault_contract,
+        &VaultQueryMsg::UserPosition {
+            vault_id,
+            address: env.contract.address.to_string(),
+        },
+    )?;
+    let total: Uint128 = position.shares.parse()?;
+    let held = VAULT_SHARES_HELD
+        .may_load(deps.storage, vault_id)?
+        .unwrap_or_default();
+    let minted = total.checked_sub(held).map_err(StdError::from)?;
+    VAULT_SHARES_HELD.save(deps.storage, vault_id, &total)?;
+
+    pot.vault_shares += minted;
+    POTS.save(deps.storage, pot_id, &pot)?;
+
+    Ok(Response::new().add_event(evt_vault_deposit(pot_id, vault_id, &amount, &minted)))
+}
+
+/// Pays out a pot whose vault shares have just been redeemed.
+fn reply_vault_withdraw(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let pending = PENDING_WITHDRAWAL.load(deps.storage)?;
+    PENDING_WITHDRAWAL.remove(deps.storage);
+    let mut pot = POTS.load(deps.storage, pending.pot_id)?;
+    let vault_id = pot.yield_vault_id.ok_or(VaultNotConfigured)?;
+
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &pot.goal.denom)?
+        .amount;
+    let received = balance
+        .checked_sub(pending.balance_before)
+        .map_err(StdError::from)?;
+    let yield_amount = received.saturating_sub(pot.current);
+    let shares = pot.vault_shares;
+    pot.vault_shares = Uint128::zero();
+    POTS.save(deps.storage, pot.id, &pot)?;
+
+    let mut res = Response::new().add_event(evt_vault_withdraw(
+        pot.id,
+        vault_id,
+        &shares,
+        &received,
+        &yield_amount,
+    ));
+    let penalty = pending
+        .penalty_bps
+        .map_or(Uint128::zero(), |bps| calculate_fee(received, bps));
+    let payout = received - penalty + pending.bonus;
+    if !payout.is_zero() {
+        res = res.add_message(BankMsg::Send {
+            to_address: pot.owner.to_string(),
+            amount: vec![Coin::new(payout.u128(), pot.goal.denom.clone())],
+        });
+    }
+    take_penalty(deps.storage, &pot, penalty, res)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => {
@@ -220,20 +860,16 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_json_binary(&ConfigResp {
                 admin: c.admin.to_string(),
                 default_denom: c.default_denom,
+                treasury: match c.penalty_recipient {
+                    PenaltyRecipient::Treasury(t) => Some(t.to_string()),
+                    PenaltyRecipient::Savers => None,
+                },
+                vault_contract: c.vault_contract.map(|a| a.to_string()),
             })
         }
         QueryMsg::GetPot { id } => {
             let p = POTS.load(deps.storage, id)?;
-            to_json_binary(&PotResp {
-                id: p.id,
-                owner: p.owner.to_string(),
-                goal: p.goal,
-                current: p.current.to_string(),
-                label: p.label,
-                created_at: p.created_at,
-                closed: p.closed,
-                broken: p.broken,
-            })
+            to_json_binary(&pot_resp(p))
         }
         QueryMsg::ListPotsByOwner {
             owner,
@@ -259,44 +895,100 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
                 .take(limit)
                 .map(|&id| {
                     let p = POTS.load(deps.storage, id)?;
-                    Ok(PotResp {
-                        id: p.id,
-                        owner: p.owner.to_string(),
-                        goal: p.goal,
-                        current: p.current.to_string(),
-                        label: p.label,
-                        created_at: p.created_at,
-                        closed: p.closed,
-                        broken: p.broken,
-                    })
+                    Ok(pot_resp(p))
                 })
                 .collect();
 
             to_json_binary(&pots?)
         }
         QueryMsg::ListAllPots { start_after, limit } => {
-            let start = start_after.map(|id| Bound::<u64>::exclusive(id));
+            let start = start_after.map(Bound::<u64>::exclusive);
             let limit = limit.unwrap_or(30) as usize;
 
             let pots: StdResult<Vec<_>> = POTS
                 .range(deps.storage, start, None, Order::Ascending)
                 .take(limit)
                 .map(|item| {
-                    let (id, p) = item?;
-                    Ok(PotResp {
-                        id,
-                        owner: p.owner.to_string(),
-                        goal: p.goal,
-                        current: p.current.to_string(),
-                        label: p.label,
-                        created_at: p.created_at,
-                        closed: p.closed,
-                        broken: p.broken,
-                    })
+                    let (_, p) = item?;
+                    Ok(pot_resp(p))
                 })
                 .collect();
 
             to_json_binary(&pots?)
         }
+        QueryMsg::ListDepositors {
+            pot_id,
+            start_after,
+            limit,
+        } => {
+            let start_addr = start_after
+                .map(|a| deps.api.addr_validate(&a))
+                .transpose()?;
+            let start = start_addr.map(Bound::exclusive);
+            let limit = limit.unwrap_or(30) as usize;
+
+            let depositors: StdResult<Vec<_>> = DEPOSITS
+                .prefix(pot_id)
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| {
+                    let (addr, d) = item?;
+                    Ok(DepositorResp {
+                        depositor: addr.to_string(),
+                        amount: d.amount.to_string(),
+                        deposit_count: d.deposit_count,
+                        last_deposit_at: d.last_deposit_at,
+                    })
+                })
+                .collect();
+
+            to_json_binary(&depositors?)
+        }
+        QueryMsg::GetAutoSave { pot_id } => {
+            let a = AUTO_SAVES.load(deps.storage, pot_id)?;
+            to_json_binary(&AutoSaveResp {
+                pot_id,
+                amount: a.amount.to_string(),
+                interval_secs: a.interval_secs,
+                next_due_ts: a.next_due_ts,
+            })
+        }
+        QueryMsg::AutoSaveBalance { owner } => {
+            let owner = deps.api.addr_validate(&owner)?;
+            let balance = AUTO_SAVE_BALANCES
+                .may_load(deps.storage, owner.clone())?
+                .unwrap_or_default();
+            to_json_binary(&AutoSaveBalanceResp {
+                owner: owner.to_string(),
+                balance: balance.to_string(),
+            })
+        }
+        QueryMsg::PenaltyPool {} => to_json_binary(&PenaltyPoolResp {
+            pool: PENALTY_POOL
+                .may_load(deps.storage)?
+                .unwrap_or_default()
+                .to_string(),
+            locked_savings: LOCKED_SAVINGS
+                .may_load(deps.storage)?
+                .unwrap_or_default()
+                .to_string(),
+        }),
+    }
+}
+
+fn pot_resp(p: Pot) -> PotResp {
+    PotResp {
+        id: p.id,
+        owner: p.owner.to_string(),
+        goal: p.goal,
+        current: p.current.to_string(),
+        label: p.label,
+        created_at: p.created_at,
+        closed: p.closed,
+        broken: p.broken,
+        unlock_at: p.unlock_at,
+        early_break_penalty_bps: p.early_break_penalty_bps,
+        yield_vault_id: p.yield_vault_id,
+        vault_shares: p.vault_shares.to_string(),
     }
 }
@@ -24,6 +24,9 @@ pub enum ContractError {
     #[error("Invalid deposit amount")]
     InvalidDepositAmount,
 
+    #[error("Attached funds must match the deposit amount")]
+    FundsMismatch,
+
     #[error("Goal not reached")]
     GoalNotReached,
 
@@ -35,4 +38,31 @@ pub enum ContractError {
 
     #[error("Pot is locked")]
     PotLocked,
+
+    #[error("Unlock time must be in the future")]
+    InvalidUnlockTime,
+
+    #[error("Auto-save amount and interval must be non-zero")]
+    InvalidAutoSave,
+
+    #[error("No auto-save scheduled for this pot")]
+    AutoSaveNotFound,
+
+    #[error("Insufficient auto-save balance")]
+    InsufficientAutoSaveBalance,
+
+    #[error("Vault contract not configured")]
+    VaultNotConfigured,
+
+    #[error("Vault not found")]
+    VaultNotFound,
+
+    #[error("Pots still hold shares in the current vault contract")]
+    VaultSharesOutstanding,
+
+    #[error("Unknown reply id {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("Early break penalty requires an unlock time and must be between 1 and 10000 bps")]
+    InvalidPenalty,
 }
@@ -14,10 +14,17 @@ pub fn evt_open_pot(id: u64, owner: &str, goal: &Coin, label: Option<&str>) -> E
     event
 }
 
-pub fn evt_deposit_pot(pot_id: u64, owner: &str, amount: &Uint128, denom: &str) -> Event {
+pub fn evt_deposit_pot(
+    pot_id: u64,
+    owner: &str,
+    depositor: &str,
+    amount: &Uint128,
+    denom: &str,
+) -> Event {
     Event::new("seimoney.deposit_pot")
         .add_attribute("pot_id", pot_id.to_string())
         .add_attribute("owner", owner.to_string())
+        .add_attribute("depositor", depositor.to_string())
         .add_attribute("amount", amount.to_string())
         .add_attribute("denom", denom.to_string())
 }
@@ -37,3 +44,96 @@ pub fn evt_close_pot(pot_id: u64, owner: &str, total_amount: &Uint128, denom: &s
         .add_attribute("total_amount", total_amount.to_string())
         .add_attribute("denom", denom.to_string())
 }
+
+pub fn evt_early_break_penalty(
+    pot_id: u64,
+    owner: &str,
+    penalty: &Uint128,
+    denom: &str,
+    recipient: &str,
+) -> Event {
+    Event::new("seimoney.early_break_penalty")
+        .add_attribute("pot_id", pot_id.to_string())
+        .add_attribute("owner", owner.to_string())
+        .add_attribute("penalty", penalty.to_string())
+        .add_attribute("denom", denom.to_string())
+        .add_attribute("recipient", recipient.to_string())
+}
+
+pub fn evt_saver_bonus(pot_id: u64, owner: &str, bonus: &Uint128, denom: &str) -> Event {
+    Event::new("seimoney.saver_bonus")
+        .add_attribute("pot_id", pot_id.to_string())
+        .add_attribute("owner", owner.to_string())
+        .add_attribute("bonus", bonus.to_string())
+        .add_attribute("denom", denom.to_string())
+}
+
+pub fn evt_set_auto_save(
+    pot_id: u64,
+    owner: &str,
+    amount: &Uint128,
+    interval_secs: u64,
+    next_due_ts: u64,
+) -> Event {
+    Event::new("seimoney.set_auto_save")
+        .add_attribute("pot_id", pot_id.to_string())
+        .add_attribute("owner", owner.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("interval_secs", interval_secs.to_string())
+        .add_attribute("next_due_ts", next_due_ts.to_string())
+}
+
+pub fn evt_auto_save(
+    pot_id: u64,
+    owner: &str,
+    amount: &Uint128,
+    denom: &str,
+    next_due_ts: u64,
+) -> Event {
+    Event::new("seimoney.auto_save")
+        .add_attribute("pot_id", pot_id.to_string())
+        .add_attribute("owner", owner.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("denom", denom.to_string())
+        .add_attribute("next_due_ts", next_due_ts.to_string())
+}
+
+pub fn evt_auto_save_skipped(pot_id: u64, owner: &str, amount: &Uint128, next_due_ts: u64) -> Event {
+    Event::new("seimoney.auto_save_skipped")
+        .add_attribute("pot_id", pot_id.to_string())
+        .add_attribute("owner", owner.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("reason", "insufficient_balance")
+        .add_attribute("next_due_ts", next_due_ts.to_string())
+}
+
+pub fn evt_auto_save_funds(owner: &str, amount: &Uint128, denom: &str, balance: &Uint128) -> Event {
+    Event::new("seimoney.auto_save_funds")
+        .add_attribute("owner", owner.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("denom", denom.to_string())
+        .add_attribute("balance", balance.to_string())
+}
+
+pub fn evt_vault_deposit(pot_id: u64, vault_id: u64, amount: &Uint128, shares: &Uint128) -> Event {
+    Event::new("seimoney.pot_vault_deposit")
+        .add_attribute("pot_id", pot_id.to_string())
+        .add_attribute("vault_id", vault_id.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("shares", shares.to_string())
+}
+
+pub fn evt_vault_withdraw(
+    pot_id: u64,
+    vault_id: u64,
+    shares: &Uint128,
+    amount: &Uint128,
+    yield_amount: &Uint128,
+) -> Event {
+    Event::new("seimoney.pot_vault_withdraw")
+        .add_attribute("pot_id", pot_id.to_string())
+        .add_attribute("vault_id", vault_id.to_string())
+        .add_attribute("shares", shares.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("yield_amount", yield_amount.to_string())
+}
@@ -1,10 +1,20 @@
-use cosmwasm_std::Coin;
+use cosmwasm_std::{Coin, Uint128};
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub admin: Option<String>,
     pub default_denom: String,
+    /// Defaults to sharing penalties among savers
+    pub penalty_recipient: Option<PenaltyRecipientMsg>,
+    /// `seimoney-vaults` contract used by pots opened with a `yield_vault_id`
+    pub vault_contract: Option<String>,
+}
+
+#[cw_serde]
+pub enum PenaltyRecipientMsg {
+    Treasury(String),
+    Savers {},
 }
 
 #[cw_serde]
@@ -12,6 +22,12 @@ pub enum ExecuteMsg {
     OpenPot {
         goal: Coin,
         label: Option<String>,
+        /// Funds cannot be taken out with BreakPot or ClosePot before this time
+        unlock_at: Option<u64>,
+        /// Allows breaking a locked pot early, minus this penalty
+        early_break_penalty_bps: Option<u16>,
+        /// Forwards deposits to this vault of the configured vault contract
+        yield_vault_id: Option<u64>,
     },
     DepositPot {
         pot_id: u64,
@@ -23,6 +39,30 @@ pub enum ExecuteMsg {
     ClosePot {
         pot_id: u64,
     },
+    /// Admin only. The vault contract cannot change while pots hold shares in it.
+    UpdateConfig {
+        penalty_recipient: Option<PenaltyRecipientMsg>,
+        vault_contract: Option<String>,
+    },
+    /// Tops up the sender's auto-save balance with the attached funds
+    DepositAutoSaveFunds {},
+    WithdrawAutoSaveFunds {
+        amount: Uint128,
+    },
+    /// Owner only: deposits `amount` from the auto-save balance every
+    /// `interval_secs`, starting one interval from now. Replaces any existing schedule.
+    SetAutoSave {
+        pot_id: u64,
+        amount: Uint128,
+        interval_secs: u64,
+    },
+    CancelAutoSave {
+        pot_id: u64,
+    },
+    /// Executes auto-saves that are due, at most `limit` of them. Anyone may call it.
+    ProcessAutoSaves {
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
@@ -36,12 +76,23 @@ pub enum QueryMsg {
     ListPotsByOwner { owner: String, start_after: Option<u64>, limit: Option<u32> },
     #[returns(Vec<PotResp>)]
     ListAllPots { start_after: Option<u64>, limit: Option<u32> },
+    #[returns(Vec<DepositorResp>)]
+    ListDepositors { pot_id: u64, start_after: Option<String>, limit: Option<u32> },
+    #[returns(PenaltyPoolResp)]
+    PenaltyPool {},
+    #[returns(AutoSaveResp)]
+    GetAutoSave { pot_id: u64 },
+    #[returns(AutoSaveBalanceResp)]
+    AutoSaveBalance { owner: String },
 }
 
 #[cw_serde]
 pub struct ConfigResp {
     pub admin: String,
     pub default_denom: String,
+    /// None when penalties are shared among savers
+    pub treasury: Option<String>,
+    pub vault_contract: Option<String>,
 }
 
 #[cw_serde]
@@ -54,4 +105,66 @@ pub struct PotResp {
     pub created_at: u64,
     pub closed: bool,
     pub broken: bool,
+    pub unlock_at: Option<u64>,
+    pub early_break_penalty_bps: Option<u16>,
+    pub yield_vault_id: Option<u64>,
+    pub vault_shares: String,
+}
+
+#[cw_serde]
+pub struct DepositorResp {
+    pub depositor: String,
+    pub amount: String,
+    pub deposit_count: u32,
+    pub last_deposit_at: u64,
+}
+
+#[cw_serde]
+pub struct PenaltyPoolResp {
+    pub pool: String,
+    pub locked_savings: String,
+}
+
+#[cw_serde]
+pub struct AutoSaveResp {
+    pub pot_id: u64,
+    pub amount: String,
+    pub interval_secs: u64,
+    pub next_due_ts: u64,
+}
+
+#[cw_serde]
+pub struct AutoSaveBalanceResp {
+    pub owner: String,
+    pub balance: String,
+}
+
+/// Subset of the `seimoney-vaults` execute interface used by vault-backed pots.
+#[cw_serde]
+pub enum VaultExecuteMsg {
+    Deposit { vault_id: u64, amount: Coin },
+    Withdraw { vault_id: u64, shares: String },
+}
+
+/// Subset of the `seimoney-vaults` query interface used by vault-backed pots.
+#[cw_serde]
+pub enum VaultQueryMsg {
+    GetVault { id: u64 },
+    UserPosition { vault_id: u64, address: String },
+}
+
+/// The fields of the vault's `VaultResp` that pots care about; the rest are ignored.
+#[derive(serde::Deserialize)]
+pub struct VaultInfo {
+    pub id: u64,
+    pub denom: String,
+}
+
+#[cw_serde]
+pub struct VaultPositionResp {
+    pub vault_id: u64,
+    pub address: String,
+    pub shares: String,
+    pub value: String,
+    pub deposited_at: u64,
 }
@@ -1,10 +1,21 @@
-use cosmwasm_std::{Addr, Coin, Uint128};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Coin, Decimal, Empty, Uint128};
+use cw_storage_plus::{Deque, Item, Map};
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
 pub struct Config {
     pub admin: Addr,
     pub default_denom: String,
+    pub penalty_recipient: PenaltyRecipient,
+    /// `seimoney-vaults` contract that vault-backed pots deposit into
+    pub vault_contract: Option<Addr>,
+}
+
+/// Where early-break penalties end up
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+pub enum PenaltyRecipient {
+    Treasury(Addr),
+    /// Pooled and shared pro-rata among time-locked pots that pay out without penalty
+    Savers,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
@@ -17,9 +28,64 @@ pub struct Pot {
     pub created_at: u64,
     pub closed: bool,
     pub broken: bool,
+    pub unlock_at: Option<u64>,
+    pub early_break_penalty_bps: Option<u16>,
+    /// Deposits are forwarded to this vault; `current` stays the principal
+    pub yield_vault_id: Option<u64>,
+    #[serde(default)]
+    pub vault_shares: Uint128,
+    /// `PENALTY_INDEX` when the pot's share of penalties was last settled
+    #[serde(default)]
+    pub penalty_index: Decimal,
+    /// Share of penalties earned up to `penalty_index`
+    #[serde(default)]
+    pub accrued_bonus: Uint128,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+pub struct Deposit {
+    pub amount: Uint128,
+    pub deposit_count: u32,
+    pub last_deposit_at: u64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+pub struct AutoSave {
+    pub amount: Uint128,
+    pub interval_secs: u64,
+    pub next_due_ts: u64,
+}
+
+/// A vault withdrawal in flight, paid out to the pot owner in `reply`
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+pub struct PendingWithdrawal {
+    pub pot_id: u64,
+    pub balance_before: Uint128,
+    /// Early-break penalty, charged on what the vault returns
+    pub penalty_bps: Option<u16>,
+    pub bonus: Uint128,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const NEXT_POT_ID: Item<u64> = Item::new("next_pot_id");
 pub const POTS: Map<u64, Pot> = Map::new("pots");
 pub const OWNER_POTS: Map<Addr, Vec<u64>> = Map::new("owner_pots");
+// Penalties waiting to be shared out, and the savings they are shared against
+pub const PENALTY_POOL: Item<Uint128> = Item::new("penalty_pool");
+pub const LOCKED_SAVINGS: Item<Uint128> = Item::new("locked_savings");
+// Penalty earned per locked token so far, checkpointed by each pot as its balance changes
+pub const PENALTY_INDEX: Item<Decimal> = Item::new("penalty_index");
+// Penalties that came in while nothing was locked, shared out with the next one
+pub const UNALLOCATED_PENALTIES: Item<Uint128> = Item::new("unallocated_penalties");
+// Running total per (pot, depositor); anyone may top up someone else's pot
+pub const DEPOSITS: Map<(u64, Addr), Deposit> = Map::new("deposits");
+// Recurring deposits per pot, drawn from the owner's auto-save balance
+pub const AUTO_SAVES: Map<u64, AutoSave> = Map::new("auto_saves");
+// Scheduled auto-saves keyed by (next_due_ts, pot_id), walked by ProcessAutoSaves
+pub const AUTO_SAVE_QUEUE: Map<(u64, u64), Empty> = Map::new("auto_save_queue");
+pub const AUTO_SAVE_BALANCES: Map<Addr, Uint128> = Map::new("auto_save_balances");
+// (pot_id, amount) of vault deposits waiting for their reply, in submission order
+pub const PENDING_VAULT_DEPOSITS: Deque<(u64, Uint128)> = Deque::new("pending_vault_deposits");
+pub const PENDING_WITHDRAWAL: Item<PendingWithdrawal> = Item::new("pending_withdrawal");
+// Vault shares owned by this contract and attributed to pots, per vault
+pub const VAULT_SHARES_HELD: Map<u64, Uint128> = Map::new("vault_shares_held");
@@ -1,8 +1,11 @@
-use cosmwasm_std::{Addr, Coin, Timestamp};
+use cosmwasm_std::{Addr, Coin, Timestamp, Uint128};
 use cw_multi_test::{App, Contract, ContractWrapper, Executor};
 use seimoney_pots::{
-    contract::{execute, instantiate, query},
-    msg::{ExecuteMsg, InstantiateMsg, QueryMsg, PotResp},
+    contract::{execute, instantiate, query, reply},
+    msg::{
+        AutoSaveBalanceResp, AutoSaveResp, DepositorResp, ExecuteMsg, InstantiateMsg,
+        PenaltyPoolResp, PenaltyRecipientMsg, PotResp, QueryMsg,
+    },
     state::Pot,
 };
 
@@ -11,16 +14,44 @@ const USER1: &str = "sei1user1";
 const USER2: &str = "sei1user2";
 
 fn mock_app() -> App {
-    App::default()
+    let mut app = App::default();
+
+    // Fund test users
+    for user in [USER1, USER2] {
+        app.sudo(cw_multi_test::SudoMsg::Bank(cw_multi_test::BankSudo::Mint {
+            to_address: user.to_string(),
+            amount: vec![Coin::new(10000, "usei")],
+        }))
+        .unwrap();
+    }
+
+    app
 }
 
 fn setup_contract(app: &mut App) -> Addr {
-    let code = ContractWrapper::new(execute, instantiate, query);
+    setup_contract_with_penalty_recipient(app, None)
+}
+
+fn setup_contract_with_penalty_recipient(
+    app: &mut App,
+    penalty_recipient: Option<PenaltyRecipientMsg>,
+) -> Addr {
+    setup_contract_with_config(app, penalty_recipient, None)
+}
+
+fn setup_contract_with_config(
+    app: &mut App,
+    penalty_recipient: Option<PenaltyRecipientMsg>,
+    vault_contract: Option<String>,
+) -> Addr {
+    let code = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
     let code_id = app.store_code(Box::new(code));
     
     let msg = InstantiateMsg {
         admin: Some(ADMIN.to_string()),
         default_denom: "usei".to_string(),
+        penalty_recipient,
+        vault_contract,
     };
     
     app.instantiate_contract(
@@ -51,6 +82,9 @@ fn test_open_pot() {
     let msg = ExecuteMsg::OpenPot {
         goal: Coin::new(5000, "usei"),
         label: Some("Vacation Fund".to_string()),
+        unlock_at: None,
+        early_break_penalty_bps: None,
+        yield_vault_id: None,
     };
     
     let result = app.execute_contract(
@@ -85,6 +119,9 @@ fn test_deposit_pot() {
     let open_msg = ExecuteMsg::OpenPot {
         goal: Coin::new(5000, "usei"),
         label: Some("Vacation Fund".to_string()),
+        unlock_at: None,
+        early_break_penalty_bps: None,
+        yield_vault_id: None,
     };
     
     app.execute_contract(
@@ -127,6 +164,9 @@ fn test_break_pot() {
     let open_msg = ExecuteMsg::OpenPot {
         goal: Coin::new(5000, "usei"),
         label: Some("Vacation Fund".to_string()),
+        unlock_at: None,
+        early_break_penalty_bps: None,
+        yield_vault_id: None,
     };
     
     app.execute_contract(
@@ -177,6 +217,9 @@ fn test_close_pot() {
     let open_msg = ExecuteMsg::OpenPot {
         goal: Coin::new(5000, "usei"),
         label: Some("Vacation Fund".to_string()),
+        unlock_at: None,
+        early_break_penalty_bps: None,
+        yield_vault_id: None,
     };
     
     app.execute_contract(
@@ -227,6 +270,9 @@ fn test_cannot_break_unbreakable_pot() {
     let open_msg = ExecuteMsg::OpenPot {
         goal: Coin::new(5000, "usei"),
         label: Some("Vacation Fund".to_string()),
+        unlock_at: None,
+        early_break_penalty_bps: None,
+        yield_vault_id: None,
     };
     
     app.execute_contract(
@@ -259,6 +305,9 @@ fn test_list_pots() {
         let open_msg = ExecuteMsg::OpenPot {
             goal: Coin::new(1000 * i as u128, "usei"),
             label: Some(format!("Pot {}", i)),
+            unlock_at: None,
+            early_break_penalty_bps: None,
+            yield_vault_id: None,
         };
         
         app.execute_contract(
@@ -289,6 +338,9 @@ fn test_list_owner_pots() {
         let open_msg = ExecuteMsg::OpenPot {
             goal: Coin::new(1000 * (i + 1) as u128, "usei"),
             label: Some(format!("Pot for {}", user)),
+            unlock_at: None,
+            early_break_penalty_bps: None,
+            yield_vault_id: None,
         };
         
         app.execute_contract(
@@ -309,3 +361,661 @@ fn test_list_owner_pots() {
     assert_eq!(result.len(), 1);
     assert_eq!(result[0].owner, USER1);
 }
+
+fn balance(app: &App, addr: &str) -> Uint128 {
+    app.wrap().query_balance(addr, "usei").unwrap().amount
+}
+
+fn open_pot(app: &mut App, contract_addr: &Addr, goal: u128) {
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::OpenPot {
+            goal: Coin::new(goal, "usei"),
+            label: None,
+            unlock_at: None,
+            early_break_penalty_bps: None,
+            yield_vault_id: None,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_deposit_requires_matching_funds() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+    open_pot(&mut app, &contract_addr, 5000);
+
+    let deposit = ExecuteMsg::DepositPot {
+        pot_id: 1,
+        amount: Coin::new(1000, "usei"),
+    };
+
+    // No funds attached
+    let err = app
+        .execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &deposit, &[])
+        .unwrap_err();
+    assert!(err.root_cause().to_string().contains("Attached funds"));
+
+    // Less than declared
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &deposit,
+            &[Coin::new(10, "usei")],
+        )
+        .unwrap_err();
+    assert!(err.root_cause().to_string().contains("Attached funds"));
+
+    let pot: PotResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetPot { id: 1 })
+        .unwrap();
+    assert_eq!(pot.current, "0");
+    assert_eq!(balance(&app, contract_addr.as_str()), Uint128::zero());
+}
+
+#[test]
+fn test_third_party_deposits_and_ledger() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+    open_pot(&mut app, &contract_addr, 3000);
+
+    for (user, amount) in [(USER1, 1000u128), (USER2, 1500), (USER2, 500)] {
+        app.execute_contract(
+            Addr::unchecked(user),
+            contract_addr.clone(),
+            &ExecuteMsg::DepositPot {
+                pot_id: 1,
+                amount: Coin::new(amount, "usei"),
+            },
+            &[Coin::new(amount, "usei")],
+        )
+        .unwrap();
+    }
+
+    let depositors: Vec<DepositorResp> = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::ListDepositors {
+                pot_id: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(depositors.len(), 2);
+    assert_eq!(depositors[0].depositor, USER1);
+    assert_eq!(depositors[0].amount, "1000");
+    assert_eq!(depositors[0].deposit_count, 1);
+    assert_eq!(depositors[1].depositor, USER2);
+    assert_eq!(depositors[1].amount, "2000");
+    assert_eq!(depositors[1].deposit_count, 2);
+
+    // Pagination continues after the given depositor
+    let page: Vec<DepositorResp> = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::ListDepositors {
+                pot_id: 1,
+                start_after: Some(USER1.to_string()),
+                limit: Some(10),
+            },
+        )
+        .unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].depositor, USER2);
+
+    // Contract holds exactly what was deposited and pays it all out on close
+    assert_eq!(balance(&app, contract_addr.as_str()), Uint128::new(3000));
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::ClosePot { pot_id: 1 },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(balance(&app, contract_addr.as_str()), Uint128::zero());
+    assert_eq!(balance(&app, USER1), Uint128::new(12000));
+    assert_eq!(balance(&app, USER2), Uint128::new(8000));
+}
+
+fn open_locked_pot(
+    app: &mut App,
+    contract_addr: &Addr,
+    owner: &str,
+    deposit: u128,
+    early_break_penalty_bps: Option<u16>,
+) {
+    let unlock_at = app.block_info().time.seconds() + 100;
+    app.execute_contract(
+        Addr::unchecked(owner),
+        contract_addr.clone(),
+        &ExecuteMsg::OpenPot {
+            goal: Coin::new(5000, "usei"),
+            label: None,
+            unlock_at: Some(unlock_at),
+            early_break_penalty_bps,
+            yield_vault_id: None,
+        },
+        &[],
+    )
+    .unwrap();
+    let pot_id = app
+        .wrap()
+        .query_wasm_smart::<Vec<PotResp>>(
+            contract_addr.clone(),
+            &QueryMsg::ListAllPots {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap()
+        .len() as u64;
+    app.execute_contract(
+        Addr::unchecked(owner),
+        contract_addr.clone(),
+        &ExecuteMsg::DepositPot {
+            pot_id,
+            amount: Coin::new(deposit, "usei"),
+        },
+        &[Coin::new(deposit, "usei")],
+    )
+    .unwrap();
+}
+
+fn break_pot(app: &mut App, contract_addr: &Addr, owner: &str, pot_id: u64) -> String {
+    app.execute_contract(
+        Addr::unchecked(owner),
+        contract_addr.clone(),
+        &ExecuteMsg::BreakPot { pot_id },
+        &[],
+    )
+    .map(|_| String::new())
+    .unwrap_or_else(|e| e.root_cause().to_string())
+}
+
+#[test]
+fn test_time_locked_pot() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+
+    // A penalty needs an unlock time, and the unlock time must be in the future
+    for (unlock_at, bps) in [
+        (None, Some(500u16)),
+        (Some(app.block_info().time.seconds()), None),
+        (Some(app.block_info().time.seconds() + 10), Some(10_001)),
+    ] {
+        let res = app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::OpenPot {
+                goal: Coin::new(5000, "usei"),
+                label: None,
+                unlock_at,
+                early_break_penalty_bps: bps,
+                yield_vault_id: None,
+            },
+            &[],
+        );
+        assert!(res.is_err());
+    }
+
+    open_locked_pot(&mut app, &contract_addr, USER1, 1000, None);
+    assert_eq!(break_pot(&mut app, &contract_addr, USER1, 1), "Pot is locked");
+
+    app.update_block(|b| b.time = b.time.plus_seconds(100));
+    assert_eq!(break_pot(&mut app, &contract_addr, USER1, 1), "");
+    assert_eq!(balance(&app, USER1), Uint128::new(10000));
+}
+
+#[test]
+fn test_early_break_penalty_to_treasury() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract_with_penalty_recipient(
+        &mut app,
+        Some(PenaltyRecipientMsg::Treasury("sei1treasury".to_string())),
+    );
+
+    open_locked_pot(&mut app, &contract_addr, USER1, 1000, Some(1000));
+    assert_eq!(break_pot(&mut app, &contract_addr, USER1, 1), "");
+
+    assert_eq!(balance(&app, "sei1treasury"), Uint128::new(100));
+    assert_eq!(balance(&app, USER1), Uint128::new(9900));
+    assert_eq!(balance(&app, contract_addr.as_str()), Uint128::zero());
+}
+
+#[test]
+fn test_early_break_penalty_shared_with_savers() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+
+    open_locked_pot(&mut app, &contract_addr, USER1, 1000, Some(1000));
+    open_locked_pot(&mut app, &contract_addr, USER2, 2000, Some(1000));
+
+    // USER1 gives up 10% to the remaining savers
+    assert_eq!(break_pot(&mut app, &contract_addr, USER1, 1), "");
+    assert_eq!(balance(&app, USER1), Uint128::new(9900));
+
+    let pool: PenaltyPoolResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::PenaltyPool {})
+        .unwrap();
+    assert_eq!(pool.pool, "100");
+    assert_eq!(pool.locked_savings, "2000");
+
+    // USER2 holds until unlock and collects the pool
+    app.update_block(|b| b.time = b.time.plus_seconds(100));
+    assert_eq!(break_pot(&mut app, &contract_addr, USER2, 2), "");
+    assert_eq!(balance(&app, USER2), Uint128::new(10100));
+    assert_eq!(balance(&app, contract_addr.as_str()), Uint128::zero());
+}
+
+#[test]
+fn test_close_locked_pot() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+
+    // USER2 hits the goal straight away but is still locked in
+    open_locked_pot(&mut app, &contract_addr, USER2, 5000, None);
+    open_locked_pot(&mut app, &contract_addr, USER1, 1000, Some(1000));
+    assert_eq!(break_pot(&mut app, &contract_addr, USER1, 2), "");
+
+    let close = ExecuteMsg::ClosePot { pot_id: 1 };
+    let err = app
+        .execute_contract(Addr::unchecked(USER2), contract_addr.clone(), &close, &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Pot is locked");
+
+    let pool: PenaltyPoolResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::PenaltyPool {})
+        .unwrap();
+    assert_eq!(pool.pool, "100");
+    assert_eq!(pool.locked_savings, "5000");
+
+    // Once unlocked the pot closes with the whole pool as its bonus
+    app.update_block(|b| b.time = b.time.plus_seconds(100));
+    app.execute_contract(Addr::unchecked(USER2), contract_addr.clone(), &close, &[])
+        .unwrap();
+    assert_eq!(balance(&app, USER2), Uint128::new(10100));
+    assert_eq!(balance(&app, contract_addr.as_str()), Uint128::zero());
+}
+
+#[test]
+fn test_flash_deposit_earns_no_penalty_share() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+    let attacker = "sei1attacker";
+    app.sudo(cw_multi_test::SudoMsg::Bank(cw_multi_test::BankSudo::Mint {
+        to_address: attacker.to_string(),
+        amount: vec![Coin::new(99000, "usei")],
+    }))
+    .unwrap();
+
+    // USER1 forfeits 100 to USER2, the only saver locked at the time
+    open_locked_pot(&mut app, &contract_addr, USER2, 2000, Some(1000));
+    open_locked_pot(&mut app, &contract_addr, USER1, 1000, Some(1000));
+    assert_eq!(break_pot(&mut app, &contract_addr, USER1, 2), "");
+
+    // A large deposit locked for a second afterwards does not dilute that share
+    let unlock_at = app.block_info().time.seconds() + 1;
+    app.execute_contract(
+        Addr::unchecked(attacker),
+        contract_addr.clone(),
+        &ExecuteMsg::OpenPot {
+            goal: Coin::new(99000, "usei"),
+            label: None,
+            unlock_at: Some(unlock_at),
+            early_break_penalty_bps: None,
+            yield_vault_id: None,
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(attacker),
+        contract_addr.clone(),
+        &ExecuteMsg::DepositPot {
+            pot_id: 3,
+            amount: Coin::new(99000, "usei"),
+        },
+        &[Coin::new(99000, "usei")],
+    )
+    .unwrap();
+    app.update_block(|b| b.time = b.time.plus_seconds(1));
+    assert_eq!(break_pot(&mut app, &contract_addr, attacker, 3), "");
+    assert_eq!(balance(&app, attacker), Uint128::new(99000));
+
+    app.update_block(|b| b.time = b.time.plus_seconds(100));
+    assert_eq!(break_pot(&mut app, &contract_addr, USER2, 1), "");
+    assert_eq!(balance(&app, USER2), Uint128::new(10100));
+    assert_eq!(balance(&app, contract_addr.as_str()), Uint128::zero());
+}
+
+fn process_auto_saves(app: &mut App, contract_addr: &Addr) -> usize {
+    let res = app
+        .execute_contract(
+            Addr::unchecked("sei1scheduler"),
+            contract_addr.clone(),
+            &ExecuteMsg::ProcessAutoSaves { limit: None },
+            &[],
+        )
+        .unwrap();
+    res.events
+        .iter()
+        .filter(|e| e.ty == "wasm-seimoney.auto_save")
+        .count()
+}
+
+#[test]
+fn test_auto_save() {
+    let mut app = mock_app();
+    let contract_addr = setup_contract(&mut app);
+    open_pot(&mut app, &contract_addr, 5000);
+
+    // Only the owner can schedule, and the schedule must be non-trivial
+    let schedule = ExecuteMsg::SetAutoSave {
+        pot_id: 1,
+        amount: Uint128::new(400),
+        interval_secs: 86400,
+    };
+    assert!(app
+        .execute_contract(Addr::unchecked(USER2), contract_addr.clone(), &schedule, &[])
+        .is_err());
+    assert!(app
+        .execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::SetAutoSave {
+                pot_id: 1,
+                amount: Uint128::new(400),
+                interval_secs: 0,
+            },
+            &[],
+        )
+        .is_err());
+
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::DepositAutoSaveFunds {},
+        &[Coin::new(1000, "usei")],
+    )
+    .unwrap();
+    app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &schedule, &[])
+        .unwrap();
+
+    // Nothing is due yet
+    assert_eq!(process_auto_saves(&mut app, &contract_addr), 0);
+
+    // Two missed days only produce one save; the next one is due a day later
+    app.update_block(|b| b.time = b.time.plus_seconds(2 * 86400 + 10));
+    assert_eq!(process_auto_saves(&mut app, &contract_addr), 1);
+    let auto_save: AutoSaveResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetAutoSave { pot_id: 1 })
+        .unwrap();
+    assert_eq!(
+        auto_save.next_due_ts,
+        app.block_info().time.seconds() - 10 + 86400
+    );
+
+    app.update_block(|b| b.time = b.time.plus_seconds(86400));
+    assert_eq!(process_auto_saves(&mut app, &contract_addr), 1);
+
+    // 200 left in the balance, so the third save is skipped
+    app.update_block(|b| b.time = b.time.plus_seconds(86400));
+    assert_eq!(process_auto_saves(&mut app, &contract_addr), 0);
+
+    let pot: PotResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetPot { id: 1 })
+        .unwrap();
+    assert_eq!(pot.current, "800");
+    let balance_resp: AutoSaveBalanceResp = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr.clone(),
+            &QueryMsg::AutoSaveBalance {
+                owner: USER1.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(balance_resp.balance, "200");
+
+    // Cancel and withdraw what is left
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::CancelAutoSave { pot_id: 1 },
+        &[],
+    )
+    .unwrap();
+    assert!(app
+        .wrap()
+        .query_wasm_smart::<AutoSaveResp>(contract_addr.clone(), &QueryMsg::GetAutoSave { pot_id: 1 })
+        .is_err());
+    assert!(app
+        .execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::WithdrawAutoSaveFunds {
+                amount: Uint128::new(201),
+            },
+            &[],
+        )
+        .is_err());
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::WithdrawAutoSaveFunds {
+            amount: Uint128::new(200),
+        },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(balance(&app, USER1), Uint128::new(9200));
+    assert_eq!(balance(&app, contract_addr.as_str()), Uint128::new(800));
+}
+
+fn setup_vault_contract(app: &mut App) -> Addr {
+    let code = ContractWrapper::new(
+        seimoney_vaults::contract::execute,
+        seimoney_vaults::contract::instantiate,
+        seimoney_vaults::contract::query,
+    );
+    let code_id = app.store_code(Box::new(code));
+    let vault_addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(ADMIN),
+            &seimoney_vaults::msg::InstantiateMsg {
+                admin: None,
+                default_denom: "usei".to_string(),
+                max_fee_bps: None,
+            },
+            &[],
+            "SeiMoney Vaults",
+            None,
+        )
+        .unwrap();
+    app.execute_contract(
+        Addr::unchecked(ADMIN),
+        vault_addr.clone(),
+        &seimoney_vaults::msg::ExecuteMsg::CreateVault {
+            label: "Savings".to_string(),
+            denom: "usei".to_string(),
+            strategy: seimoney_vaults::msg::StrategyConfig::Conservative,
+            fee_bps: None,
+        },
+        &[],
+    )
+    .unwrap();
+    vault_addr
+}
+
+#[test]
+fn test_vault_backed_pot() {
+    let mut app = mock_app();
+    let vault_addr = setup_vault_contract(&mut app);
+    let contract_addr = setup_contract_with_config(&mut app, None, Some(vault_addr.to_string()));
+
+    // The vault must exist
+    let open = |vault_id| ExecuteMsg::OpenPot {
+        goal: Coin::new(1500, "usei"),
+        label: None,
+        unlock_at: None,
+        early_break_penalty_bps: None,
+        yield_vault_id: Some(vault_id),
+    };
+    let err = app
+        .execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &open(7), &[])
+        .unwrap_err();
+    assert!(err.root_cause().to_string().contains("Vault not found"));
+    for user in [USER1, USER2] {
+        app.execute_contract(Addr::unchecked(user), contract_addr.clone(), &open(1), &[])
+            .unwrap();
+    }
+
+    // Deposits land in the vault and are tracked as shares per pot
+    for (user, pot_id, amount) in [(USER1, 1, 1000u128), (USER2, 2, 300), (USER2, 1, 500)] {
+        app.execute_contract(
+            Addr::unchecked(user),
+            contract_addr.clone(),
+            &ExecuteMsg::DepositPot {
+                pot_id,
+                amount: Coin::new(amount, "usei"),
+            },
+            &[Coin::new(amount, "usei")],
+        )
+        .unwrap();
+    }
+    assert_eq!(balance(&app, contract_addr.as_str()), Uint128::zero());
+    assert_eq!(balance(&app, vault_addr.as_str()), Uint128::new(1800));
+
+    let pot: PotResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetPot { id: 1 })
+        .unwrap();
+    assert_eq!(pot.current, "1500");
+    assert_eq!(pot.vault_shares, "1500");
+    assert_eq!(pot.yield_vault_id, Some(1));
+
+    // The vault contract is pinned while pots hold shares in it
+    let switch_vault = ExecuteMsg::UpdateConfig {
+        penalty_recipient: None,
+        vault_contract: Some("sei1othervault".to_string()),
+    };
+    let err = app
+        .execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &switch_vault, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Pots still hold shares in the current vault contract"
+    );
+
+    // Closing redeems the pot's shares and pays out what the vault returned
+    let res = app
+        .execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::ClosePot { pot_id: 1 },
+            &[],
+        )
+        .unwrap();
+    let withdraw = res
+        .events
+        .iter()
+        .find(|e| e.ty == "wasm-seimoney.pot_vault_withdraw")
+        .unwrap();
+    assert!(withdraw
+        .attributes
+        .iter()
+        .any(|a| a.key == "amount" && a.value == "1500"));
+
+    assert_eq!(balance(&app, USER1), Uint128::new(10500));
+    assert_eq!(balance(&app, vault_addr.as_str()), Uint128::new(300));
+    assert_eq!(balance(&app, contract_addr.as_str()), Uint128::zero());
+
+    let pot: PotResp = app
+        .wrap()
+        .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetPot { id: 1 })
+        .unwrap();
+    assert!(pot.closed);
+    assert_eq!(pot.vault_shares, "0");
+
+    // The other pot keeps its shares and can still be broken
+    assert_eq!(break_pot(&mut app, &contract_addr, USER2, 2), "");
+    assert_eq!(balance(&app, USER2), Uint128::new(9500));
+    assert_eq!(balance(&app, vault_addr.as_str()), Uint128::zero());
+
+    app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &switch_vault, &[])
+        .unwrap();
+}
+
+#[test]
+fn test_vault_backed_pot_early_break_penalty() {
+    let mut app = mock_app();
+    let vault_addr = setup_vault_contract(&mut app);
+    let contract_addr = setup_contract_with_config(
+        &mut app,
+        Some(PenaltyRecipientMsg::Treasury("sei1treasury".to_string())),
+        Some(vault_addr.to_string()),
+    );
+
+    let unlock_at = app.block_info().time.seconds() + 100;
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::OpenPot {
+            goal: Coin::new(5000, "usei"),
+            label: None,
+            unlock_at: Some(unlock_at),
+            early_break_penalty_bps: Some(1000),
+            yield_vault_id: Some(1),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        contract_addr.clone(),
+        &ExecuteMsg::DepositPot {
+            pot_id: 1,
+            amount: Coin::new(1000, "usei"),
+        },
+        &[Coin::new(1000, "usei")],
+    )
+    .unwrap();
+
+    // The penalty is taken from what the vault returns, once it has returned it
+    let res = app
+        .execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::BreakPot { pot_id: 1 },
+            &[],
+        )
+        .unwrap();
+    let penalty = res
+        .events
+        .iter()
+        .find(|e| e.ty == "wasm-seimoney.early_break_penalty")
+        .unwrap();
+    assert!(penalty
+        .attributes
+        .iter()
+        .any(|a| a.key == "penalty" && a.value == "100"));
+
+    assert_eq!(balance(&app, "sei1treasury"), Uint128::new(100));
+    assert_eq!(balance(&app, USER1), Uint128::new(9900));
+    assert_eq!(balance(&app, vault_addr.as_str()), Uint128::zero());
+    assert_eq!(balance(&app, contract_addr.as_str()), Uint128::zero());
+}
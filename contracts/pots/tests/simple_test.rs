@@ -33,6 +33,8 @@ fn setup_contract(app: &mut App) -> Addr {
     let msg = InstantiateMsg {
         admin: Some(ADMIN.to_string()),
         default_denom: "usei".to_string(),
+        penalty_recipient: None,
+        vault_contract: None,
     };
     
     app.instantiate_contract(
@@ -63,6 +65,9 @@ fn test_open_pot() {
     let msg = ExecuteMsg::OpenPot {
         goal: Coin::new(5000, "usei"),
         label: Some("Vacation Fund".to_string()),
+        unlock_at: None,
+        early_break_penalty_bps: None,
+        yield_vault_id: None,
     };
     
     let result = app.execute_contract(
@@ -96,6 +101,9 @@ fn test_deposit_pot() {
     let open_msg = ExecuteMsg::OpenPot {
         goal: Coin::new(5000, "usei"),
         label: Some("Vacation Fund".to_string()),
+        unlock_at: None,
+        early_break_penalty_bps: None,
+        yield_vault_id: None,
     };
     
     app.execute_contract(